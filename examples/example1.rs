@@ -17,7 +17,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let main_js_path = dirname.join("./js/main.js"); // 构造 JS 文件路径
 
-    runtime.execute(&main_js_path.to_string_lossy()).await;
+    runtime.execute(&main_js_path.to_string_lossy()).await?;
 
     Ok(()) // 返回成功
 }
@@ -0,0 +1,52 @@
+use zjs::{op, v8, BuiltinModule, ExecuteOptions, JsRuntime, RuntimeOptions};
+
+op! {
+    /// 模拟数据库查询
+    fn query(sql: String) -> Result<Vec<String>, String> {
+        Ok(vec![format!("row of `{}`", sql)])
+    }
+}
+
+/// 注册自己的内置模块, 在 JS 中通过名称 import
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let db_name = String::from("main"); // 导出的值可以捕获 Rust 中的状态
+    let db = BuiltinModule::new("app:db")
+        .function("query", query)
+        .value("version", |scope| v8::Number::new(scope, 1.0).into())
+        .value("name", move |scope| v8::String::new(scope, &db_name).unwrap().into());
+    let utils = BuiltinModule::from_source(
+        "app:utils",
+        r#"
+            import { query } from "app:db"
+            export const first = (sql) => query(sql)[0]
+        "#,
+    );
+
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        extensions: vec![db, utils],
+        ..Default::default()
+    });
+
+    let source = r#"
+        import { name, version } from "app:db"
+        import { first } from "app:utils"
+
+        export async function main() {
+            try {
+                await import("app:unknown")
+            } catch (e) {
+                print(e.message) // Cannot find module 'app:unknown'
+            }
+
+            return `${name}@${version}: ${first("select 1")}`
+        }
+    "#;
+
+    let result = runtime
+        .eval_module_with_options("extensions.js", source, ExecuteOptions::new())
+        .await?;
+    println!("{:?}", result); // String("main@1: row of `select 1`")
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::{path::Path, thread};
+use zjs::{ExecuteOptions, JsRuntime};
+
+/// 在多个线程中同时创建并运行 JsRuntime, 每个线程使用自己的 tokio 运行时
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let filename = file!(); // 获取当前文件相对路径（编译期宏）
+    let dirname = Path::new(filename).parent().unwrap_or(Path::new("")); // 获取目录
+    let utils_js_path = dirname.join("./js/utils.js"); // 构造 JS 文件路径
+
+    let handles = (0..4)
+        .map(|index| {
+            let utils_js_path = utils_js_path.clone();
+            thread::spawn(move || {
+                let tokio_runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+
+                tokio_runtime.block_on(async {
+                    // 调用 utils.js 导出的 createFileContent 函数
+                    let mut runtime = JsRuntime::new();
+                    let options = ExecuteOptions::new()
+                        .entrypoint("createFileContent")
+                        .arg(format!("thread {}", index));
+                    runtime
+                        .execute_with_options(&utils_js_path.to_string_lossy(), options)
+                        .await
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        let result = handle.join().expect("线程执行失败")?;
+        println!("{:?}", result);
+    }
+
+    Ok(()) // 返回成功
+}
@@ -0,0 +1,52 @@
+use zjs::{op, ExecuteOptions, JsRuntime, OpFunction, RuntimeOptions};
+
+op! {
+    /// 同步 op: 两个数相加
+    fn add(a: f64, b: f64) -> Result<f64, String> {
+        Ok(a + b)
+    }
+}
+
+op! {
+    /// 异步 op: 读取文本文件, 在 JS 中返回 Promise
+    async fn read_text(path: String) -> Result<String, std::io::Error> {
+        tokio::fs::read_to_string(path).await
+    }
+}
+
+/// 通过 op! 把 Rust 函数注入到全局对象, 在内存中的模块里调用它们
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        ops: vec![
+            OpFunction::new("add", add),
+            OpFunction::new("readText", read_text),
+        ],
+        ..Default::default()
+    });
+
+    let source = r#"
+        export async function main() {
+            try {
+                add("1", 2)
+            } catch (e) {
+                print(`${e.name}: ${e.code}`) // TypeError: ERR_INVALID_ARG_TYPE
+            }
+
+            try {
+                await readText("./not-exists.txt")
+            } catch (e) {
+                print(e.code) // ENOENT
+            }
+
+            return add(40, 2)
+        }
+    "#;
+
+    let result = runtime
+        .eval_module_with_options("ops.js", source, ExecuteOptions::new())
+        .await?;
+    println!("{:?}", result); // Number(42.0)
+
+    Ok(())
+}
@@ -1,13 +1,22 @@
 use dashmap::DashMap; // 线程安全哈希表
+use serde::Serialize;
 use std::{
     future::Future,
     ptr::NonNull,
-    sync::atomic::{AtomicU32, Ordering},
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+    sync::Arc,
 };
+use tokio::sync::Notify; // 异步通知
 use v8::{Global, Local, Promise, PromiseResolver};
 
+use crate::error::JsError;
+use crate::op::OpError;
+use crate::serde_v8;
+use crate::unhandled_rejection::process_unhandled_rejections;
+use crate::value::JsValue;
+
 /// 异步任务调度器的 trait（接口）
-pub trait AsyncTaskDispatcher: Default {
+pub(crate) trait AsyncTaskDispatcher: Default {
     type AsyncTaskResult; // 关联类型：任务结果
 
     /// 创建异步任务，返回 Promise
@@ -19,12 +28,66 @@ pub trait AsyncTaskDispatcher: Default {
     where
         F: Future<Output = Self::AsyncTaskResult> + Send + 'static; // F 必须是 Send + 'static
 
-    /// 运行事件循环，处理所有完成的异步任务
+    /// 是否还有保持事件循环存活（ref）的任务或句柄
+    fn has_pending_tasks(&self) -> bool;
+
+    /// 等待下一个完成的异步任务，并 resolve/reject 对应的 Promise
+    ///
+    /// # 返回
+    /// 没有保持事件循环存活的任务时直接返回 false，否则处理一个任务后返回 true
+    fn poll_event_loop(&mut self, scope: &mut v8::HandleScope<'_>) -> impl Future<Output = bool>;
+
+    /// 运行事件循环，处理所有完成的异步任务，直到没有待处理的任务时退出
+    ///
+    /// 每次清空微任务队列后检查未处理的 Promise rejection，策略要求失败时返回错误
     fn run_event_loop(
         &mut self,
-        isolate: &mut v8::Isolate,
         scope: &mut v8::HandleScope<'_>,
-    ) -> impl Future<Output = ()>; // 返回异步操作
+    ) -> impl Future<Output = Result<(), JsError>> {
+        async move {
+            loop {
+                // 先清空同步代码留下的微任务（例如已 resolve 的 Promise 的 then 回调）
+                scope.perform_microtask_checkpoint();
+                if scope.is_execution_terminating() {
+                    return Err(JsError::Terminated); // 执行已被终止, 不再处理任何任务
+                }
+                process_unhandled_rejections(scope)?;
+
+                if !self.poll_event_loop(scope).await {
+                    break; // 所有任务都已完成, 退出事件循环
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// 运行事件循环，直到 promise 完成，或者已经没有任务能让它完成时退出
+    fn run_event_loop_until(
+        &mut self,
+        scope: &mut v8::HandleScope<'_>,
+        promise: Local<'_, Promise>,
+    ) -> impl Future<Output = Result<(), JsError>> {
+        async move {
+            loop {
+                scope.perform_microtask_checkpoint();
+                if scope.is_execution_terminating() {
+                    return Err(JsError::Terminated); // 执行已被终止, 不再处理任何任务
+                }
+                process_unhandled_rejections(scope)?;
+
+                if promise.state() != v8::PromiseState::Pending {
+                    break; // Promise 已经完成
+                }
+
+                if !self.poll_event_loop(scope).await {
+                    break; // 没有待处理的任务, Promise 不会再完成了
+                }
+            }
+
+            Ok(())
+        }
+    }
 }
 
 /// 异步任务完成消息
@@ -45,34 +108,224 @@ pub enum AsyncTaskResult {
 /// 内部异步任务结构
 struct AsyncTask {
     promise_resolver: NonNull<PromiseResolver>, // Promise 解析器的非空指针
+    refed: bool,                                // 是否保持事件循环存活
+    abort_handle: tokio::task::AbortHandle,     // 用于在执行被终止时取消 Tokio 任务
 }
 
 /// 异步任务的值类型
 #[derive(Debug)]
 pub enum AsyncTaskValue {
-    String(Vec<u8>), // 字符串（字节向量）
-    Number(i32),     // 数字
-    Undefined,       // undefined
+    String(String), // 字符串
+    Number(f64),    // 数字
+    Bool(bool),     // 布尔值
+    Null,           // null
+    Undefined,      // undefined
+    BigInt(i128),   // BigInt
+    Bytes(Vec<u8>), // 二进制数据，转换为 Uint8Array
+    Value(JsValue), // 任意结构化数据（对象、数组等），通过 AsyncTaskValue::serialize 创建
+    // Error 实例，name 为 TypeError、RangeError 等内置错误时创建对应类型的实例，code 例如 "ENOENT"
+    Error {
+        name: String,
+        code: Option<String>,
+        message: String,
+    },
 }
 
 pub(crate) type TaskID = u32; // 任务 ID 类型别名
 
+/// 事件循环的句柄引用计数 - 线程安全，可以克隆后交给异步任务或其他线程
+///
+/// 长生命周期的句柄（如监听中的 socket）在存活期间 ref，关闭时 unref。
+/// unref 会唤醒正在等待的事件循环，让它在引用归零且没有任务时退出
+#[derive(Clone, Default)]
+pub struct EventLoopHandle {
+    inner: Arc<EventLoopRefs>,
+}
+
+#[derive(Default)]
+struct EventLoopRefs {
+    refs: AtomicUsize, // 保持事件循环存活的句柄数量
+    notify: Notify,    // 引用变化时唤醒事件循环
+}
+
+impl EventLoopHandle {
+    /// 增加一次句柄引用，使事件循环保持运行
+    pub fn ref_handle(&self) {
+        self.inner.refs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 释放一次句柄引用，并唤醒事件循环重新检查是否还有待处理的工作
+    pub fn unref_handle(&self) {
+        // 防止多次 unref 导致计数下溢
+        let _ = self
+            .inner
+            .refs
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |refs| {
+                refs.checked_sub(1)
+            });
+        self.inner.notify.notify_one();
+    }
+
+    /// 是否还有保持事件循环存活的句柄
+    pub fn has_refs(&self) -> bool {
+        self.inner.refs.load(Ordering::Relaxed) > 0
+    }
+
+    /// 等待引用变化的通知（没有等待者时 notify_one 会保留一次通知，不会丢失）
+    async fn notified(&self) {
+        self.inner.notify.notified().await;
+    }
+}
+
 /// Tokio 异步任务管理器 - 使用 Tokio 运行时管理异步任务
 pub struct TokioAsyncTaskManager {
     tasks: DashMap<TaskID, AsyncTask>, // 任务存储（ID -> 任务）
     channel_sender: tokio::sync::mpsc::Sender<AsyncTaskMessage>, // 通道发送端
     channel_receiver: tokio::sync::mpsc::Receiver<AsyncTaskMessage>, // 通道接收端
+    event_loop_handle: EventLoopHandle, // 保持事件循环存活的句柄引用
 }
 
 impl TokioAsyncTaskManager {
     /// 创建新的 TokioAsyncTaskManager
     pub fn new() -> Self {
-        let (sender, receiver) = tokio::sync::mpsc::channel(100); // 创建容量为 100 的通道
+        Self::with_channel_capacity(100)
+    }
+
+    /// 创建指定通道容量的 TokioAsyncTaskManager
+    ///
+    /// 容量决定了同时完成、尚未被事件循环处理的任务最多能有多少个，超出时任务会等待
+    pub fn with_channel_capacity(capacity: usize) -> Self {
+        let (sender, receiver) = tokio::sync::mpsc::channel(capacity); // 创建指定容量的通道
         TokioAsyncTaskManager {
             tasks: DashMap::new(), // 初始化空 HashMap
             channel_sender: sender,
             channel_receiver: receiver,
+            event_loop_handle: EventLoopHandle::default(),
+        }
+    }
+
+    /// 创建不保持事件循环存活（unref）的异步任务，返回 Promise
+    ///
+    /// 事件循环不会为了等待这类任务而继续运行，适用于后台轮询等可有可无的工作
+    pub fn create_unref_async_task<'s, F>(
+        &self,
+        scope: &mut v8::HandleScope<'s>,
+        async_block: F,
+    ) -> Local<'s, Promise>
+    where
+        F: Future<Output = AsyncTaskResult> + Send + 'static,
+    {
+        self.spawn_task(scope, async_block, false)
+    }
+
+    /// 为长生命周期句柄（如监听中的 socket）增加一次引用，使事件循环保持运行
+    pub fn ref_handle(&self) {
+        self.event_loop_handle.ref_handle();
+    }
+
+    /// 释放一次句柄引用，引用归零后事件循环可以在任务完成时退出
+    pub fn unref_handle(&self) {
+        self.event_loop_handle.unref_handle();
+    }
+
+    /// 获取事件循环的句柄引用计数，可以移动到异步任务或其他线程中 ref/unref
+    pub fn event_loop_handle(&self) -> EventLoopHandle {
+        self.event_loop_handle.clone()
+    }
+
+    /// 取消所有尚未完成的任务，释放它们的 Promise 解析器，并清除句柄引用
+    ///
+    /// 在执行被终止和切换到新的上下文时调用，之前的上下文中的任务不再阻塞事件循环，
+    /// 完成后也不会 resolve 属于已丢弃的上下文的 Promise
+    ///
+    /// # 参数
+    /// - `isolate`: 任务所属的 V8 隔离区，用于释放 Promise 解析器的 Global 句柄
+    pub(crate) fn cancel_all_tasks(&mut self, isolate: &mut v8::Isolate) {
+        let task_ids: Vec<TaskID> = self.tasks.iter().map(|task| *task.key()).collect();
+        for task_id in task_ids {
+            if let Some((_, task)) = self.tasks.remove(&task_id) {
+                task.abort_handle.abort();
+                drop(unsafe { Global::from_raw(isolate, task.promise_resolver) });
+                // 释放 Global 句柄
+            }
+        }
+
+        // 丢弃已经完成但还没有处理的任务结果（之后才送达的结果找不到任务, 会被忽略）
+        while self.channel_receiver.try_recv().is_ok() {}
+
+        // 之前取得的句柄继续 ref/unref 旧的计数, 不再影响新的事件循环
+        self.event_loop_handle = EventLoopHandle::default();
+    }
+
+    /// 将任务加入循环队列并交给 Tokio 执行，返回 Promise
+    fn spawn_task<'s, F>(
+        &self,
+        scope: &mut v8::HandleScope<'s>,
+        async_block: F,
+        refed: bool, // 是否保持事件循环存活
+    ) -> Local<'s, Promise>
+    where
+        F: Future<Output = AsyncTaskResult> + Send + 'static,
+    {
+        let promise_resolver = v8::PromiseResolver::new(scope).unwrap(); // 创建 Promise 解析器
+        let promise = promise_resolver.get_promise(scope); // 从解析器获取 Promise
+        let promise_resolver = Global::new(scope, promise_resolver); // 包装成 Global（可跨作用域）
+
+        let task_id = generate_task_id(); // 生成唯一任务 ID
+
+        // 生成 Tokio 异步任务（任务的结果只在事件循环中处理, 所以可以在存储任务之前开始执行）
+        let join_handle = tokio::spawn({
+            let channel_sender = self.channel_sender.clone(); // 克隆通道发送端
+            async move {
+                // 等待异步块完成
+                let task_value = async_block.await;
+                let task_message = AsyncTaskMessage {
+                    task_id,
+                    payload: task_value,
+                };
+                channel_sender.send(task_message).await.unwrap(); // 通过通道发送结果
+            }
+        });
+
+        // 将任务存储到 DashMap
+        self.tasks.insert(
+            task_id,
+            AsyncTask {
+                promise_resolver: promise_resolver.into_raw(), // 转换为原始指针
+                refed,
+                abort_handle: join_handle.abort_handle(),
+            },
+        );
+
+        promise // 返回 Promise
+    }
+
+    /// 根据任务结果 resolve/reject 任务对应的 Promise
+    fn settle_task(&self, scope: &mut v8::HandleScope<'_>, message: AsyncTaskMessage) {
+        // 从存储中移除任务
+        let Some((_, task)) = self.tasks.remove(&message.task_id) else {
+            return;
+        };
+
+        // 还原 Promise 解析器
+        let promise_resolver = unsafe { Global::from_raw(scope, task.promise_resolver) };
+
+        // 根据结果类型处理 Promise
+        match message.payload {
+            // 成功
+            AsyncTaskResult::Resolve(task_value) => {
+                let v8_value = task_value.into_v8(scope); // 转换为 V8 值
+                promise_resolver.open(scope).resolve(scope, v8_value); // Resolve Promise
+            }
+            // 失败
+            AsyncTaskResult::Reject(task_value) => {
+                let v8_value = task_value.into_v8(scope);
+                promise_resolver.open(scope).reject(scope, v8_value); // Reject Promise
+            }
         }
+
+        // perform_microtask_checkpoint: reslove 对应 promise, 强制让 V8 清空微任务队列，立即执行所有 pending 的 then/catch/queueMicrotask 这样相关的回调
+        scope.perform_microtask_checkpoint();
     }
 }
 
@@ -94,6 +347,12 @@ where
     unsafe { &*value_ptr }.create_async_task(scope, async_block) // 调用管理器创建任务
 }
 
+/// 从 V8 作用域获取事件循环的句柄引用计数
+pub(crate) fn event_loop_handle_from_scope(scope: &mut v8::HandleScope) -> EventLoopHandle {
+    let value_ptr = scope.get_data(0) as *mut TokioAsyncTaskManager; // 从作用域获取管理器指针
+    unsafe { &*value_ptr }.event_loop_handle()
+}
+
 impl Default for TokioAsyncTaskManager {
     fn default() -> Self {
         Self::new()
@@ -112,62 +371,32 @@ impl AsyncTaskDispatcher for TokioAsyncTaskManager {
     where
         F: Future<Output = Self::AsyncTaskResult> + Send + 'static,
     {
-        let promise_resolver = v8::PromiseResolver::new(scope).unwrap(); // 创建 Promise 解析器
-        let promise = promise_resolver.get_promise(scope); // 从解析器获取 Promise
-        let promise_resolver = Global::new(scope, promise_resolver); // 包装成 Global（可跨作用域）
-
-        let task_id = generate_task_id(); // 生成唯一任务 ID
-
-        // 将任务存储到 DashMap
-        self.tasks.insert(
-            task_id,
-            AsyncTask {
-                promise_resolver: promise_resolver.into_raw(), // 转换为原始指针
-            },
-        );
-
-        // 生成 Tokio 异步任务
-        tokio::spawn({
-            let channel_sender = self.channel_sender.clone(); // 克隆通道发送端
-            async move {
-                // 等待异步块完成
-                let task_value = async_block.await;
-                let task_message = AsyncTaskMessage {
-                    task_id,
-                    payload: task_value,
-                };
-                channel_sender.send(task_message).await.unwrap(); // 通过通道发送结果
-            }
-        });
+        self.spawn_task(scope, async_block, true) // 默认保持事件循环存活
+    }
 
-        promise // 返回 Promise
+    /// 存在 ref 的任务或句柄时事件循环需要继续运行
+    fn has_pending_tasks(&self) -> bool {
+        self.event_loop_handle.has_refs() || self.tasks.iter().any(|task| task.refed)
     }
 
-    /// 运行事件循环，监听任务完成并 resolve/reject Promise
-    async fn run_event_loop(&mut self, isolate: &mut v8::Isolate, scope: &mut v8::HandleScope<'_>) {
-        while let Some(message) = self.channel_receiver.recv().await {
-            // 接收任务完成消息, 从存储中移除任务
-            if let Some((_, task)) = self.tasks.remove(&message.task_id) {
-                // 还原 Promise 解析器
-                let promise_resolver = unsafe { Global::from_raw(isolate, task.promise_resolver) };
-
-                // 根据结果类型处理 Promise
-                match message.payload {
-                    // 成功
-                    AsyncTaskResult::Resolve(task_value) => {
-                        let v8_value = task_value.into_v8(scope); // 转换为 V8 值
-                        promise_resolver.open(scope).resolve(scope, v8_value); // Resolve Promise
-                    }
-                    // 失败
-                    AsyncTaskResult::Reject(task_value) => {
-                        let v8_value = task_value.into_v8(scope);
-                        promise_resolver.open(scope).reject(scope, v8_value); // Reject Promise
-                    }
-                }
+    /// 监听任务完成并 resolve/reject Promise
+    async fn poll_event_loop(&mut self, scope: &mut v8::HandleScope<'_>) -> bool {
+        if !self.has_pending_tasks() {
+            return false;
+        }
 
-                // perform_microtask_checkpoint: reslove 对应 promise, 强制让 V8 清空微任务队列，立即执行所有 pending 的 then/catch/queueMicrotask 这样相关的回调
-                isolate.perform_microtask_checkpoint();
+        // 管理器自身持有发送端，通道不会关闭，所以只在确实有待处理工作时才等待消息；
+        // 句柄 unref 时同样唤醒, 由下一轮重新检查是否还有待处理的工作
+        let message = tokio::select! {
+            message = self.channel_receiver.recv() => message,
+            _ = self.event_loop_handle.notified() => return true,
+        };
+        match message {
+            Some(message) => {
+                self.settle_task(scope, message);
+                true
             }
+            None => false,
         }
     }
 }
@@ -176,14 +405,191 @@ impl AsyncTaskValue {
     /// 转换 AsyncTaskValue 为 V8 值
     pub fn into_v8<'s>(self, scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Value> {
         match self {
-            AsyncTaskValue::String(value) => {
-                // 如果是字符串
-                v8::String::new(scope, std::str::from_utf8(&value).unwrap_or_default()) // 转换为 V8 字符串
-                    .unwrap()
-                    .into()
-            }
-            AsyncTaskValue::Number(value) => v8::Number::new(scope, value as f64).into(), // 转换为 V8 数字
+            AsyncTaskValue::String(value) => v8::String::new(scope, &value).unwrap().into(), // 转换为 V8 字符串
+            AsyncTaskValue::Number(value) => v8::Number::new(scope, value).into(), // 转换为 V8 数字
+            AsyncTaskValue::Bool(value) => v8::Boolean::new(scope, value).into(),
+            AsyncTaskValue::Null => v8::null(scope).into(),
             AsyncTaskValue::Undefined => v8::undefined(scope).into(), // 转换为 undefined
+            AsyncTaskValue::BigInt(value) => JsValue::BigInt(value).into_v8(scope),
+            AsyncTaskValue::Bytes(value) => JsValue::Bytes(value).into_v8(scope), // 转换为 Uint8Array
+            AsyncTaskValue::Value(value) => value.into_v8(scope),
+            AsyncTaskValue::Error {
+                name,
+                code,
+                message,
+            } => {
+                let message = v8::String::new(scope, &message).unwrap();
+
+                // 内置错误类型直接创建对应的实例, 其他名称创建 Error 后修改 name
+                let error = match name.as_str() {
+                    "Error" => v8::Exception::error(scope, message),
+                    "TypeError" => v8::Exception::type_error(scope, message),
+                    "RangeError" => v8::Exception::range_error(scope, message),
+                    "ReferenceError" => v8::Exception::reference_error(scope, message),
+                    "SyntaxError" => v8::Exception::syntax_error(scope, message),
+                    _ => {
+                        let error = v8::Exception::error(scope, message);
+                        let name_key = v8::String::new(scope, "name").unwrap();
+                        let name = v8::String::new(scope, &name).unwrap();
+                        error
+                            .cast::<v8::Object>()
+                            .set(scope, name_key.into(), name.into());
+                        error
+                    }
+                };
+
+                if let Some(code) = code {
+                    let code_key = v8::String::new(scope, "code").unwrap();
+                    let code = v8::String::new(scope, &code).unwrap();
+                    error
+                        .cast::<v8::Object>()
+                        .set(scope, code_key.into(), code.into());
+                }
+
+                error
+            }
+        }
+    }
+
+    /// 创建一个 Error 实例
+    ///
+    /// # 参数
+    /// - `message`: 错误信息
+    pub fn error(message: impl Into<String>) -> Self {
+        AsyncTaskValue::Error {
+            name: "Error".to_string(),
+            code: None,
+            message: message.into(),
+        }
+    }
+
+    /// 创建一个 TypeError 实例
+    ///
+    /// # 参数
+    /// - `message`: 错误信息
+    pub fn type_error(message: impl Into<String>) -> Self {
+        AsyncTaskValue::Error {
+            name: "TypeError".to_string(),
+            code: None,
+            message: message.into(),
+        }
+    }
+
+    /// 二进制数据是合法的 UTF-8 时转换为字符串，否则保留为字节（Uint8Array）
+    pub fn from_utf8_or_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(value) => AsyncTaskValue::String(value),
+            Err(e) => AsyncTaskValue::Bytes(e.into_bytes()),
         }
     }
+
+    /// 把任意实现了 Serialize 的 Rust 值转换为 AsyncTaskValue
+    ///
+    /// 序列化在调用处完成（可以在异步任务所在的线程中），转换为 V8 值时不再需要原来的类型
+    pub fn serialize<T: Serialize + ?Sized>(value: &T) -> Result<Self, serde_v8::Error> {
+        serde_v8::to_value(value).map(AsyncTaskValue::Value)
+    }
+}
+
+impl From<JsValue> for AsyncTaskValue {
+    fn from(value: JsValue) -> Self {
+        AsyncTaskValue::Value(value)
+    }
+}
+
+impl From<String> for AsyncTaskValue {
+    fn from(value: String) -> Self {
+        AsyncTaskValue::String(value)
+    }
+}
+
+impl From<&str> for AsyncTaskValue {
+    fn from(value: &str) -> Self {
+        AsyncTaskValue::String(value.to_string())
+    }
+}
+
+impl From<i32> for AsyncTaskValue {
+    fn from(value: i32) -> Self {
+        AsyncTaskValue::Number(value.into())
+    }
+}
+
+impl From<u32> for AsyncTaskValue {
+    fn from(value: u32) -> Self {
+        AsyncTaskValue::Number(value.into())
+    }
+}
+
+impl From<u64> for AsyncTaskValue {
+    // 超出安全整数范围（2^53 - 1）时转换为 BigInt, 避免丢失精度
+    fn from(value: u64) -> Self {
+        if value < 1 << 53 {
+            AsyncTaskValue::Number(value as f64)
+        } else {
+            AsyncTaskValue::BigInt(value.into())
+        }
+    }
+}
+
+impl From<f64> for AsyncTaskValue {
+    fn from(value: f64) -> Self {
+        AsyncTaskValue::Number(value)
+    }
+}
+
+impl From<bool> for AsyncTaskValue {
+    fn from(value: bool) -> Self {
+        AsyncTaskValue::Bool(value)
+    }
+}
+
+impl From<Vec<u8>> for AsyncTaskValue {
+    fn from(value: Vec<u8>) -> Self {
+        AsyncTaskValue::Bytes(value)
+    }
+}
+
+impl From<std::io::Error> for AsyncTaskValue {
+    // 转换为带有 code（例如 ENOENT）的 Error 实例, 与 Node 的文件系统错误一致
+    fn from(error: std::io::Error) -> Self {
+        OpError::from(error).into()
+    }
+}
+
+/// I/O 错误对应的 Node 错误码
+pub(crate) fn io_error_code(error: &std::io::Error) -> Option<&'static str> {
+    use std::io::ErrorKind;
+
+    let code = match error.kind() {
+        ErrorKind::NotFound => "ENOENT",
+        ErrorKind::PermissionDenied => "EACCES",
+        ErrorKind::AlreadyExists => "EEXIST",
+        ErrorKind::InvalidInput => "EINVAL",
+        ErrorKind::NotADirectory => "ENOTDIR",
+        ErrorKind::IsADirectory => "EISDIR",
+        ErrorKind::DirectoryNotEmpty => "ENOTEMPTY",
+        ErrorKind::ReadOnlyFilesystem => "EROFS",
+        ErrorKind::StorageFull => "ENOSPC",
+        ErrorKind::FileTooLarge => "EFBIG",
+        ErrorKind::ResourceBusy => "EBUSY",
+        ErrorKind::CrossesDevices => "EXDEV",
+        ErrorKind::TooManyLinks => "EMLINK",
+        ErrorKind::InvalidFilename => "ENAMETOOLONG",
+        ErrorKind::ConnectionRefused => "ECONNREFUSED",
+        ErrorKind::ConnectionReset => "ECONNRESET",
+        ErrorKind::ConnectionAborted => "ECONNABORTED",
+        ErrorKind::NotConnected => "ENOTCONN",
+        ErrorKind::AddrInUse => "EADDRINUSE",
+        ErrorKind::AddrNotAvailable => "EADDRNOTAVAIL",
+        ErrorKind::BrokenPipe => "EPIPE",
+        ErrorKind::TimedOut => "ETIMEDOUT",
+        ErrorKind::Interrupted => "EINTR",
+        ErrorKind::WouldBlock => "EAGAIN",
+        ErrorKind::Unsupported => "ENOTSUP",
+        ErrorKind::OutOfMemory => "ENOMEM",
+        _ => return None,
+    };
+
+    Some(code)
 }
@@ -110,7 +110,7 @@ fn seek_file_pos(
         let result = file_handler.seek(pos as u64).await; // 异步文件寻址
         match result {
             Ok(_) => AsyncTaskResult::Resolve(AsyncTaskValue::Undefined), // 成功返回 undefined
-            Err(e) => AsyncTaskResult::Reject(AsyncTaskValue::String(e.to_string().into_bytes())), // 错误
+            Err(e) => AsyncTaskResult::Reject(e.into()), // 错误, reject 带有 code 的 Error
         }
     });
 
@@ -119,7 +119,7 @@ fn seek_file_pos(
 
 /// 读取文件内容函数
 ///
-/// 返回一个 Promise，当读取完成时 resolve，value 为文件内容（字节数组）
+/// 返回一个 Promise，当读取完成时 resolve，value 为文件内容（UTF-8 文本为字符串，否则为 Uint8Array）
 fn read_file_content(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
@@ -131,8 +131,8 @@ fn read_file_content(
     let promise = create_async_task_from_scope(scope, async move {
         let result = file_handler.read_to_end().await; // 异步读取文件
         match result {
-            Ok(content) => AsyncTaskResult::Resolve(AsyncTaskValue::String(content)), // 返回内容
-            Err(e) => AsyncTaskResult::Reject(AsyncTaskValue::String(e.to_string().into_bytes())), // 错误
+            Ok(content) => AsyncTaskResult::Resolve(AsyncTaskValue::from_utf8_or_bytes(content)), // 返回内容, 不是 UTF-8 文本时返回 Uint8Array
+            Err(e) => AsyncTaskResult::Reject(e.into()), // 错误, reject 带有 code 的 Error
         }
     });
 
@@ -156,10 +156,15 @@ fn write_file(
         .ok()
         .map(|v| v.to_rust_string_lossy(scope));
 
-    // 如果参数不是字符串则在 JS 端抛出异常
+    // 如果参数不是字符串则在 JS 端抛出 TypeError
     let Some(new_content) = new_content else {
-        let error = v8::String::new(scope, "The \"path\" 参数必须被设置为字符串").unwrap();
-        scope.throw_exception(error.into());
+        let error = AsyncTaskValue::Error {
+            name: "TypeError".to_string(),
+            code: Some("ERR_INVALID_ARG_TYPE".to_string()),
+            message: "The \"data\" argument must be of type string".to_string(),
+        }
+        .into_v8(scope);
+        scope.throw_exception(error);
         return;
     };
 
@@ -168,8 +173,8 @@ fn write_file(
         let new_content = new_content.into_bytes(); // 转换为字节
         let result = file_handler.write(&new_content).await; // 异步写入文件
         match result {
-            Ok(_) => AsyncTaskResult::Resolve(AsyncTaskValue::Number(new_content.len() as i32)), // 返回写入字节数
-            Err(e) => AsyncTaskResult::Reject(AsyncTaskValue::String(e.to_string().into_bytes())), // 错误
+            Ok(_) => AsyncTaskResult::Resolve(AsyncTaskValue::from(new_content.len() as u64)), // 返回写入字节数
+            Err(e) => AsyncTaskResult::Reject(e.into()), // 错误, reject 带有 code 的 Error
         }
     });
 
@@ -259,9 +264,9 @@ fn open_file_handler(
         match result {
             Ok(file) => {
                 let fd = file.into_std().await.into_raw_fd(); // 获取文件描述符
-                AsyncTaskResult::Resolve(AsyncTaskValue::Number(fd)) // 返回 FD
+                AsyncTaskResult::Resolve(AsyncTaskValue::from(fd)) // 返回 FD
             }
-            Err(e) => AsyncTaskResult::Reject(AsyncTaskValue::String(e.to_string().into_bytes())), // 错误
+            Err(e) => AsyncTaskResult::Reject(e.into()), // 错误, reject 带有 code 的 Error
         }
     });
 
@@ -270,25 +275,19 @@ fn open_file_handler(
     return_value.set(promise.into()); // 返回最终 Promise
 }
 
-/// 创建文件系统模块
+/// 创建文件系统模块的 openFile 函数
 ///
-/// 返回一个对象模板，暴露 openFile 方法给 JavaScript
-pub fn create_fs<'s>(scope: &mut v8::HandleScope<'s, ()>) -> v8::Local<'s, v8::ObjectTemplate> {
-    let fs: v8::Local<'_, ObjectTemplate> = v8::ObjectTemplate::new(scope); // 创建 fs 对象(是一个模板)
-
+/// 作为 fs 模块的导出暴露给 JavaScript，default 导出的 fs 对象上也是同一个函数
+pub fn create_open_file<'s>(scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Value> {
     let file_handler_template = create_file_handler_template(scope); // 创建 File 对象(是一个模板)
     let file_handler_template_ptr = file_handler_template.into_raw(); // 获取原始指针
     let file_handler_template =
         v8::External::new(scope, file_handler_template_ptr.as_ptr() as *mut _); // v8::External 允许将 C++ 对象的指针包装成 JavaScript 值，使其能在 JavaScript 环境中传递和存储。
 
-    // 添加 openFile 方法
-    fs.set(
-        v8::String::new(scope, "openFile").unwrap().into(), // "openFile" 方法
-        v8::FunctionTemplate::builder(open_file_handler) // 创建函数
-            .data(file_handler_template.into()) // 将模板作为 data 传入, 可在 buider 回调中使用 args.data() 重新获取
-            .build(scope)
-            .into(),
-    );
-
-    fs
+    // 创建 openFile 方法
+    v8::Function::builder(open_file_handler) // 创建函数
+        .data(file_handler_template.into()) // 将模板作为 data 传入, 可在 buider 回调中使用 args.data() 重新获取
+        .build(scope)
+        .unwrap()
+        .into()
 }
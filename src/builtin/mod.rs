@@ -1,3 +1,4 @@
 // 内置模块导出
 pub mod async_task;  // 异步任务管理模块
 pub mod fs;  // 文件系统模块
+pub mod registry;  // 内置模块注册表
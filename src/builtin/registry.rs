@@ -0,0 +1,194 @@
+use std::sync::Arc; // 线程安全的共享所有权
+
+use super::fs::create_open_file; // 文件系统模块
+use crate::global::commonjs::create_require_callback; // module 模块的 createRequire
+use crate::options::Builtin; // 自带的内置模块
+
+/// 内置模块导出值的构造函数，在模块第一次被 import 时调用
+///
+/// 可以是捕获了状态的闭包，需要把状态交给导出的 JS 函数时，在其中通过
+/// `v8::Function::builder(callback).data(..)` 传入 `v8::External` 等数据
+pub type ExportBuilder =
+    Arc<dyn for<'s> Fn(&mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Value> + Send + Sync>;
+
+/// 内置模块的一个导出
+#[derive(Clone)]
+pub(crate) enum BuiltinExport {
+    Function(v8::FunctionCallback), // 由 Rust 函数实现的 JS 函数
+    Value(ExportBuilder),           // 由 Rust 代码构造的任意值
+}
+
+/// 内置模块的实现
+#[derive(Clone)]
+pub(crate) enum BuiltinSource {
+    Exports(Vec<(String, BuiltinExport)>), // 由 Rust 实现的合成模块（导出名称, 导出）
+    Source(String),                        // 由 JS 源代码实现的 ES 模块
+}
+
+/// 可以通过名称 import 的内置模块
+///
+/// # 示例
+/// ```ignore
+/// let db = BuiltinModule::new("app:db")
+///     .function("query", query) // 通过 op! 定义的函数
+///     .value("version", |scope| v8::Number::new(scope, 1.0).into());
+/// let app_name = String::from("demo");
+/// let config = BuiltinModule::new("app:config")
+///     .value("name", move |scope| v8::String::new(scope, &app_name).unwrap().into()); // 捕获状态
+/// let utils = BuiltinModule::from_source("app:utils", "export const answer = 42;");
+///
+/// let runtime = JsRuntime::with_options(RuntimeOptions {
+///     extensions: vec![db, config, utils],
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Clone)]
+pub struct BuiltinModule {
+    pub(crate) name: String,          // import 时使用的名称
+    pub(crate) source: BuiltinSource, // 模块的实现
+}
+
+impl BuiltinModule {
+    /// 创建由 Rust 实现的内置模块，之后通过 function、value 添加导出
+    ///
+    /// 没有添加 default 导出时，default 导出为包含所有导出的对象，
+    /// 因此 `import db from "app:db"` 和 `import { query } from "app:db"` 都可以使用
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: BuiltinSource::Exports(Vec::new()),
+        }
+    }
+
+    /// 创建由 JS 源代码实现的内置模块
+    ///
+    /// 源代码作为 ES 模块编译，其中只能 import 其他内置模块
+    pub fn from_source(name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: BuiltinSource::Source(source.into()),
+        }
+    }
+
+    /// 导出一个由 Rust 函数实现的 JS 函数（例如通过 op! 定义的函数）
+    pub fn function(
+        self,
+        name: impl Into<String>,
+        callback: impl v8::MapFnTo<v8::FunctionCallback>,
+    ) -> Self {
+        self.export(name.into(), BuiltinExport::Function(callback.map_fn_to()))
+    }
+
+    /// 导出一个由 Rust 代码构造的值，构造函数可以是捕获了状态的闭包
+    pub fn value(
+        self,
+        name: impl Into<String>,
+        builder: impl for<'s> Fn(&mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Value>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.export(name.into(), BuiltinExport::Value(Arc::new(builder)))
+    }
+
+    /// 添加导出，JS 源代码实现的模块忽略
+    fn export(mut self, name: String, export: BuiltinExport) -> Self {
+        if let BuiltinSource::Exports(exports) = &mut self.source {
+            exports.retain(|(export_name, _)| *export_name != name); // 同名导出以最后一次为准
+            exports.push((name, export));
+        }
+        self
+    }
+
+    /// 模块的名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Node.js 的内置模块名称，可以带 `node:` 前缀 import
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "assert/strict",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "dns/promises",
+    "domain",
+    "events",
+    "fs",
+    "fs/promises",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "path/posix",
+    "path/win32",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "readline/promises",
+    "repl",
+    "stream",
+    "stream/consumers",
+    "stream/promises",
+    "stream/web",
+    "string_decoder",
+    "timers",
+    "timers/promises",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "util/types",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// 是否为 Node.js 的内置模块名称（不带 `node:` 前缀）
+pub(crate) fn is_node_builtin(name: &str) -> bool {
+    NODE_BUILTINS.contains(&name)
+}
+
+/// 把 import 的名称转换为注册表中的名称
+///
+/// `node:fs` 与 `fs` 指向同一个内置模块，其他名称保持不变
+pub(crate) fn builtin_name(specifier: &str) -> &str {
+    match specifier.strip_prefix("node:") {
+        Some(name) if is_node_builtin(name) => name,
+        _ => specifier,
+    }
+}
+
+/// 运行时自带的内置模块
+///
+/// # 参数
+/// - `enabled`: 启用的自带内置模块
+pub(crate) fn runtime_builtins(enabled: &[Builtin]) -> Vec<BuiltinModule> {
+    enabled
+        .iter()
+        .map(|builtin| match builtin {
+            Builtin::Fs => BuiltinModule::new("fs").value("openFile", create_open_file),
+            Builtin::Module => {
+                BuiltinModule::new("module").function("createRequire", create_require_callback)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::serde_v8;
+
+/// JS 异常的详细信息
+///
+/// 由 v8::TryCatch 捕获的异常（或被 reject 的值）和对应的 v8::Message 提取而来
+#[derive(Debug, Clone, Default)]
+pub struct JsException {
+    pub message: String,       // 异常信息，例如 "TypeError: x is not a function"
+    pub stack: Option<String>, // JS 堆栈（异常对象的 stack 属性）
+    pub resource_name: Option<String>, // 出错的文件
+    pub line_number: Option<usize>, // 出错的行号（从 1 开始）
+    pub start_column: Option<usize>, // 出错的列号（从 0 开始）
+}
+
+impl JsException {
+    /// 从 TryCatch 中提取已捕获的异常
+    pub(crate) fn from_try_catch(tc_scope: &mut v8::TryCatch<'_, v8::HandleScope<'_>>) -> Self {
+        let message = tc_scope.message();
+        match tc_scope.exception() {
+            Some(exception) => Self::from_exception(tc_scope, exception, message),
+            None => Self {
+                message: "未知错误".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// 从异常值中提取信息，用于未经过 TryCatch 的异常（例如被 reject 的 Promise 的值）
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `exception`: 异常值
+    /// - `message`: 异常对应的 v8::Message，没有时根据异常值创建
+    pub(crate) fn from_exception(
+        scope: &mut v8::HandleScope<'_>,
+        exception: v8::Local<'_, v8::Value>,
+        message: Option<v8::Local<'_, v8::Message>>,
+    ) -> Self {
+        let message = message.unwrap_or_else(|| v8::Exception::create_message(scope, exception));
+
+        // 读取异常对象上的 stack 属性（只有 Error 对象才有）
+        let stack = exception
+            .try_cast::<v8::Object>()
+            .ok()
+            .and_then(|exception| {
+                let key = v8::String::new(scope, "stack").unwrap();
+                exception.get(scope, key.into())
+            })
+            .filter(|stack| stack.is_string())
+            .map(|stack| stack.to_rust_string_lossy(scope));
+
+        let resource_name = message
+            .get_script_resource_name(scope)
+            .filter(|name| !name.is_null_or_undefined())
+            .map(|name| name.to_rust_string_lossy(scope));
+
+        Self {
+            message: exception.to_rust_string_lossy(scope),
+            stack,
+            resource_name,
+            line_number: message.get_line_number(scope),
+            start_column: Some(message.get_start_column()),
+        }
+    }
+}
+
+impl fmt::Display for JsException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 堆栈中已经包含异常信息
+        write!(f, "{}", self.stack.as_deref().unwrap_or(&self.message))?;
+
+        if let (Some(resource_name), Some(line_number)) = (&self.resource_name, self.line_number) {
+            write!(f, "\n    at {}:{}", resource_name, line_number)?;
+            if let Some(start_column) = self.start_column {
+                write!(f, ":{}", start_column + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// JsRuntime 执行脚本时的错误
+#[derive(Debug, Clone)]
+pub enum JsError {
+    Load(String),                    // 入口文件无法找到或读取
+    Compile(JsException),            // 模块编译失败（语法错误等）
+    Instantiate(JsException),        // 模块实例化失败（依赖无法解析、导入的名称不存在等）
+    Evaluate(JsException),           // 模块顶层代码或入口函数执行时抛出异常
+    MissingEntrypoint(String),       // 模块没有导出指定的入口函数
+    MissingExport(String),           // 模块没有导出指定的名称
+    NotLoaded,                       // 还没有成功执行过任何模块，无法访问导出
+    UnhandledRejection(JsException), // 存在未处理的 Promise rejection（策略为 Error 时）
+    OutOfMemory,                     // 堆即将耗尽，脚本被终止
+    Terminated,                      // 脚本的执行被终止
+    Conversion(serde_v8::Error),     // 参数或结果无法在 Rust 值和 JS 值之间转换
+}
+
+impl fmt::Display for JsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsError::Load(message) => write!(f, "加载错误: {}", message),
+            JsError::Compile(exception) => write!(f, "编译错误: {}", exception),
+            JsError::Instantiate(exception) => write!(f, "实例化错误: {}", exception),
+            JsError::Evaluate(exception) => write!(f, "执行错误: {}", exception),
+            JsError::MissingEntrypoint(name) => write!(f, "入口函数 {} 不存在", name),
+            JsError::MissingExport(name) => write!(f, "导出 {} 不存在", name),
+            JsError::NotLoaded => write!(f, "还没有执行过任何模块"),
+            JsError::UnhandledRejection(exception) => {
+                write!(f, "未处理的 Promise rejection: {}", exception)
+            }
+            JsError::OutOfMemory => write!(f, "内存不足: 堆即将耗尽, 脚本已被终止"),
+            JsError::Terminated => write!(f, "脚本的执行被终止"),
+            JsError::Conversion(error) => write!(f, "转换错误: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for JsError {}
@@ -0,0 +1,440 @@
+use std::path::Path;
+
+use crate::helper::throw_error_with_code;
+
+use super::module_loader::ModuleLoader;
+use super::resolver::file_url_to_path;
+
+/// CommonJS 模块包装函数的参数，与 Node.js 一致
+const WRAPPER_PARAMETERS: &str = "exports, require, module, __filename, __dirname";
+
+/// 创建 CommonJS 的 module 对象
+///
+/// 对象含有 id、filename、loaded 和初始为空对象的 exports，执行完成后 loaded 设为 true
+pub(crate) fn create_module_object<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    path: &Path,
+) -> Option<v8::Local<'s, v8::Object>> {
+    let module = v8::Object::new(scope);
+    let filename = v8::String::new(scope, &path.to_string_lossy())?;
+    let exports = v8::Object::new(scope);
+    let loaded = v8::Boolean::new(scope, false);
+
+    set_property(scope, module, "id", filename.into())?;
+    set_property(scope, module, "filename", filename.into())?;
+    set_property(scope, module, "loaded", loaded.into())?;
+    set_property(scope, module, "exports", exports.into())?;
+
+    Some(module)
+}
+
+/// 执行 CommonJS 模块
+///
+/// 源代码包装在 `function (exports, require, module, __filename, __dirname)` 中执行，
+/// this 为 module.exports，执行完成后把 module.loaded 设为 true
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `module`: 通过 create_module_object 创建的 module 对象
+/// - `source`: 模块的源代码
+/// - `path`: 模块的绝对路径
+///
+/// # 返回
+/// 执行失败（编译错误或抛出异常）时返回 None，异常已在 JS 端抛出
+pub(crate) fn execute_module(
+    scope: &mut v8::HandleScope,
+    module: v8::Local<v8::Object>,
+    source: &str,
+    path: &Path,
+) -> Option<()> {
+    let wrapper = compile_wrapper(scope, source, path)?;
+    let require = create_require(scope, path)?;
+    let exports = get_exports(scope, module)?;
+    let filename = v8::String::new(scope, &path.to_string_lossy())?;
+    let dirname = path.parent().unwrap_or(Path::new("")).to_string_lossy();
+    let dirname = v8::String::new(scope, &dirname)?;
+
+    let args = [
+        exports,
+        require.into(),
+        module.into(),
+        filename.into(),
+        dirname.into(),
+    ];
+    wrapper.call(scope, exports, &args)?;
+
+    let loaded = v8::Boolean::new(scope, true);
+    set_property(scope, module, "loaded", loaded.into())
+}
+
+/// 获取 module.exports（模块可以把它替换为任意值）
+pub(crate) fn get_exports<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    module: v8::Local<v8::Object>,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let key = v8::String::new(scope, "exports")?;
+    module.get(scope, key.into())
+}
+
+/// 设置 module.exports
+pub(crate) fn set_exports(
+    scope: &mut v8::HandleScope,
+    module: v8::Local<v8::Object>,
+    exports: v8::Local<v8::Value>,
+) -> Option<()> {
+    set_property(scope, module, "exports", exports)
+}
+
+/// 静态分析 CommonJS 模块的源代码，找出它被 import 时的命名导出
+///
+/// 被 import 的 CommonJS 模块在 ES 模块图执行到它时才执行，链接阶段只能从源代码中识别导出的名称
+/// （与 Node.js 的 cjs-module-lexer 类似），识别以下写法：
+/// - `exports.name = ...`、`module.exports.name = ...`、`exports["name"] = ...`
+/// - `Object.defineProperty(exports, "name", ...)`
+/// - `module.exports = { name, other: ..., method() {} }`
+///
+/// 没有识别出的属性仍然可以通过 default 导出（module.exports）访问
+pub(crate) fn detect_export_names(source: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut add_name = |name: &str| {
+        if name != "default" && !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    };
+
+    for (index, _) in source.match_indices("exports") {
+        let before = &source[..index];
+        let after = &source[index + "exports".len()..];
+        if after.starts_with(is_identifier_char) {
+            continue; // 例如 exportsList
+        }
+
+        // exports 前面是 module. 或者不是标识符的一部分
+        let module_exports = before
+            .strip_suffix("module.")
+            .is_some_and(|before| !before.ends_with(is_identifier_char) && !before.ends_with('.'));
+        if !module_exports && (before.ends_with(is_identifier_char) || before.ends_with('.')) {
+            continue;
+        }
+
+        let rest = after.trim_start();
+        if let Some(rest) = rest.strip_prefix('.') {
+            // exports.name =
+            let (name, rest) = take_identifier(rest.trim_start());
+            if !name.is_empty() && is_assignment(rest) {
+                add_name(name);
+            }
+        } else if let Some(rest) = rest.strip_prefix('[') {
+            // exports["name"] =
+            let Some((name, rest)) = take_string(rest.trim_start()) else {
+                continue;
+            };
+            if let Some(rest) = rest.trim_start().strip_prefix(']') {
+                if is_assignment(rest) {
+                    add_name(name);
+                }
+            }
+        } else if module_exports && is_assignment(rest) {
+            // module.exports = { ... }
+            let rest = rest[1..].trim_start();
+            if let Some(object) = rest.strip_prefix('{') {
+                object_literal_keys(object, &mut add_name);
+            }
+        }
+    }
+
+    // Object.defineProperty(exports, "name", ...)
+    for (index, _) in source.match_indices("Object.defineProperty") {
+        let rest = source[index + "Object.defineProperty".len()..].trim_start();
+        let Some(rest) = rest.strip_prefix('(') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let rest = rest.strip_prefix("module.").unwrap_or(rest);
+        let Some(rest) = rest.strip_prefix("exports") else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix(',') else {
+            continue;
+        };
+        if let Some((name, _)) = take_string(rest.trim_start()) {
+            add_name(name);
+        }
+    }
+
+    names
+}
+
+/// 是否可以作为标识符的一部分
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// 取出开头的标识符，返回标识符和剩余的部分
+fn take_identifier(source: &str) -> (&str, &str) {
+    let end = source
+        .find(|c: char| !is_identifier_char(c))
+        .unwrap_or(source.len());
+    if source.starts_with(|c: char| c.is_ascii_digit()) {
+        return ("", source);
+    }
+    source.split_at(end)
+}
+
+/// 取出开头的字符串字面量（不含转义的单引号或双引号字符串），返回字符串内容和剩余的部分
+fn take_string(source: &str) -> Option<(&str, &str)> {
+    let quote = source.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let content = &source[1..];
+    let end = content.find([quote, '\\', '\n'])?;
+    content[end..]
+        .starts_with(quote)
+        .then(|| (&content[..end], &content[end + 1..]))
+}
+
+/// 剩余部分是否以赋值开头（排除 == 和 ===）
+fn is_assignment(source: &str) -> bool {
+    let source = source.trim_start();
+    source.starts_with('=') && !source.starts_with("==") && !source.starts_with("=>")
+}
+
+/// 读取对象字面量顶层的属性名，source 从 { 之后开始
+fn object_literal_keys(mut source: &str, add_name: &mut impl FnMut(&str)) {
+    loop {
+        source = source.trim_start();
+        if source.is_empty() || source.starts_with('}') {
+            return;
+        }
+
+        if source.starts_with("...") || source.starts_with('[') {
+            // 展开和计算属性名无法静态识别, 跳过
+        } else if let Some((key, rest)) = take_string(source) {
+            if rest.trim_start().starts_with(':') {
+                add_name(key);
+            }
+        } else {
+            let (mut key, mut rest) = take_identifier(source);
+            // get name()、set name(value)、async name() 的属性名在修饰符之后
+            if matches!(key, "get" | "set" | "async")
+                && rest.starts_with(char::is_whitespace)
+                && rest.trim_start().starts_with(is_identifier_char)
+            {
+                (key, rest) = take_identifier(rest.trim_start());
+            }
+            if !key.is_empty() && rest.trim_start().starts_with([',', '}', ':', '(']) {
+                add_name(key);
+            }
+        }
+
+        // 跳过属性的值, 直到顶层的 , 或 }
+        match skip_property_value(source) {
+            Some(rest) => source = rest,
+            None => return,
+        }
+    }
+}
+
+/// 跳过一个属性（直到顶层的逗号），返回逗号之后的部分，遇到对象结束或源代码结束时返回 None
+fn skip_property_value(source: &str) -> Option<&str> {
+    let mut depth = 0usize; // 括号嵌套层数
+    let mut quote = None; // 当前所在字符串的引号
+    let mut escaped = false;
+
+    for (index, c) in source.char_indices() {
+        if let Some(open) = quote {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == open => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            '}' => return None, // 对象结束
+            ',' if depth == 0 => return Some(&source[index + 1..]),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 创建 require 函数，相对路径基于 path 所在的目录解析
+pub(crate) fn create_require<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    path: &Path,
+) -> Option<v8::Local<'s, v8::Function>> {
+    let referrer_path = v8::String::new(scope, &path.to_string_lossy())?;
+    v8::Function::builder(require_callback)
+        .data(referrer_path.into()) // 通过 data 传入导入者路径
+        .build(scope)
+}
+
+/// module 内置模块的 createRequire(filename)
+///
+/// filename 可以是 file:// URL（例如 import.meta.url）或绝对路径，
+/// 返回的 require 与 CommonJS 模块中的 require 共用同一个模块缓存
+pub(crate) fn create_require_callback(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut return_value: v8::ReturnValue,
+) {
+    let filename = args.get(0);
+    let path = filename
+        .is_string()
+        .then(|| file_url_to_path(&filename.to_rust_string_lossy(scope)))
+        .filter(|path| path.is_absolute());
+
+    let Some(path) = path else {
+        let message = format!(
+            "The argument 'filename' must be a file URL string or absolute path string. Received {}",
+            filename.to_rust_string_lossy(scope)
+        );
+        throw_error_with_code(scope, &message, "ERR_INVALID_ARG_VALUE");
+        return;
+    };
+
+    if let Some(require) = create_require(scope, &path) {
+        return_value.set(require.into());
+    }
+}
+
+/// require 函数
+///
+/// 通过 ModuleLoader 解析并加载模块，返回 CommonJS 模块的 module.exports、JSON 文件解析后的值、
+/// ES 模块的命名空间或内置模块的 default 导出
+fn require_callback(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut return_value: v8::ReturnValue,
+) {
+    let referrer_path = args.data().to_rust_string_lossy(scope); // 导入者路径
+
+    let specifier = args.get(0);
+    if !specifier.is_string() {
+        let message = "The \"id\" argument must be of type string";
+        throw_error_with_code(scope, message, "ERR_INVALID_ARG_TYPE");
+        return;
+    }
+    let specifier_str = specifier.to_rust_string_lossy(scope);
+
+    let state_ptr = scope.get_data(1); // 获取 ModuleLoader 指针
+    if state_ptr.is_null() {
+        eprintln!("错误: 在 require_callback 中的 ModuleLoader 为空 ");
+        return;
+    }
+    let module_loader = unsafe { &mut *(state_ptr as *mut ModuleLoader) };
+
+    // 加载失败时异常已经被抛出
+    if let Some(exports) = module_loader.require(scope, &specifier_str, Path::new(&referrer_path)) {
+        return_value.set(exports);
+    }
+}
+
+/// 把 CommonJS 模块的源代码编译为包装函数
+///
+/// 包装函数与源代码的第一行写在同一行，错误信息中的行号与源文件一致
+fn compile_wrapper<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    source: &str,
+    path: &Path,
+) -> Option<v8::Local<'s, v8::Function>> {
+    // #! 开头的第一行在函数中是语法错误, 改为注释
+    let source = match source.strip_prefix("#!") {
+        Some(rest) => format!("//{}", rest),
+        None => source.to_string(),
+    };
+    let code = format!("(function ({}) {{ {}\n}})", WRAPPER_PARAMETERS, source);
+
+    let code = v8::String::new(scope, &code)?;
+    let resource_path = v8::String::new(scope, &path.to_string_lossy())?.into();
+
+    // 创建脚本来源信息, 作为经典脚本编译
+    let script_origin = v8::ScriptOrigin::new(
+        scope,
+        resource_path, // 资源路径
+        0,             // 行偏移
+        0,             // 列偏移
+        false,         // 是否是共享代码
+        0,             // 脚本 ID
+        None,          // sourcemap URL
+        false,         // 是否是 opaque
+        false,         // 是否是 wasm
+        false,         // 是否是 esm 模块
+        None,          // 主机定义的选项
+    );
+
+    let script = v8::Script::compile(scope, code, Some(&script_origin))?;
+    script.run(scope)?.try_cast::<v8::Function>().ok()
+}
+
+/// 设置对象的属性
+fn set_property(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+    name: &str,
+    value: v8::Local<v8::Value>,
+) -> Option<()> {
+    let key = v8::String::new(scope, name)?;
+    object.set(scope, key.into(), value).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect_export_names;
+
+    #[test]
+    fn detects_exports_assignments() {
+        let source = r#"
+            exports.foo = 1;
+            exports . bar = function () {};
+            module.exports.baz = 2;
+            exports["qux"] = 3;
+            Object.defineProperty(exports, "defined", { value: 4 });
+        "#;
+        assert_eq!(
+            detect_export_names(source),
+            ["foo", "bar", "baz", "qux", "defined"]
+        );
+    }
+
+    #[test]
+    fn ignores_comparisons_and_other_objects() {
+        let source = r#"
+            if (exports.foo === 1) {}
+            myexports.bar = 1;
+            other.exports.baz = 1;
+            exports.default = 1;
+        "#;
+        assert!(detect_export_names(source).is_empty());
+    }
+
+    #[test]
+    fn detects_object_literal_keys() {
+        let source = r#"
+            module.exports = {
+                a,
+                b: { nested: 1 },
+                "c-d": [1, 2],
+                method() { return { inner: 1 }; },
+                get getter() { return 1; },
+                ...spread,
+                [computed]: 1,
+                last: "}",
+            };
+        "#;
+        assert_eq!(
+            detect_export_names(source),
+            ["a", "b", "c-d", "method", "getter", "last"]
+        );
+    }
+
+    #[test]
+    fn deduplicates_names() {
+        let source = "exports.a = 1; exports.a = 2; module.exports = { a };";
+        assert_eq!(detect_export_names(source), ["a"]);
+    }
+}
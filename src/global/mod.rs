@@ -1,7 +1,13 @@
 use v8::{FunctionCallback, MapFnTo};
 
+use crate::op::OpFunction;
+use crate::options::GlobalApi;
+
+pub(crate) mod commonjs;
 pub mod module_loader;
+pub mod module_registry;
 mod print;
+mod resolver;
 
 /// 注入全局方法到全局对象模板
 ///
@@ -29,9 +35,28 @@ fn inject_global_method(
 /// 注入所有全局 API 到 V8 上下文
 ///
 /// 这个函数在创建 V8 上下文时调用，用于将 Rust 实现的全局 API 暴露给 JavaScript
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `template`: 全局对象模板
+/// - `globals`: 需要注入的全局 API
+/// - `ops`: 需要注入的 op 函数
 pub(crate) fn inject_global_values(
     scope: &mut v8::HandleScope<'_, ()>,
     template: &v8::ObjectTemplate,
+    globals: &[GlobalApi],
+    ops: &[OpFunction],
 ) {
-    inject_global_method(scope, template, "print", print::print);
+    for global in globals {
+        match global {
+            GlobalApi::Print => inject_global_method(scope, template, "print", print::print),
+        }
+    }
+
+    // op 函数已经是 V8 函数回调, 直接创建函数模板
+    for op in ops {
+        let op_name = v8::String::new(scope, &op.name).unwrap();
+        let op_func = v8::FunctionTemplate::builder_raw(op.callback).build(scope);
+        template.set(op_name.into(), op_func.into());
+    }
 }
@@ -1,47 +1,69 @@
 use std::{
     collections::BTreeMap, // 有序键值对映射
+    env,                   // 当前工作目录
     fs::{self},            // 文件系统操作
     path::{Path, PathBuf}, // 路径操作
 };
 
 use v8::CallbackScope;
 
-use crate::builtin::fs::create_fs; // 文件系统模块
+use crate::builtin::registry::{
+    builtin_name, is_node_builtin, BuiltinExport, BuiltinModule, BuiltinSource,
+}; // 内置模块注册表
+use crate::error::{JsError, JsException};
+use crate::helper::{throw_error, throw_error_with_code, throw_type_error};
+use crate::unhandled_rejection::forget_rejection;
+
+use super::commonjs; // CommonJS 模块
+use super::module_registry::{ModuleInfo, ModuleKind, ModuleRegistry}; // 模块注册表
+use super::resolver::{
+    file_url_to_path, is_commonjs, path_to_file_url, resolve_package, resolve_package_import,
+    resolve_path, ModuleType, ResolveError, IMPORT_CONDITIONS, REQUIRE_CONDITIONS,
+}; // Node.js 风格的包解析
 
 /// 模块加载器 - 管理 JS 模块的加载、编译、缓存和依赖解析
 pub struct ModuleLoader {
-    // 映射模块的唯一标识哈希值到其绝对路径
-    // 用于在模块回调中快速查询模块信息
-    id_to_path_map: BTreeMap<i32, PathBuf>,
+    // 模块注册表 - 记录所有已加载的模块（文件按绝对路径, 内置模块按名称）
+    // 在模块回调中根据 V8 模块查询它的路径和类型, 也作为模块缓存
+    modules: ModuleRegistry,
+
+    // 内置模块注册表 - 可以 import 的内置模块名称到其实现的映射
+    builtin_registry: BTreeMap<String, BuiltinModule>,
+
+    // CommonJS 模块缓存 - 根据绝对路径缓存 module 对象, require() 和 import 共用
+    commonjs_modules: BTreeMap<PathBuf, v8::Global<v8::Object>>,
 
-    // 模块缓存 - 根据绝对路径缓存已编译的模块
-    // 使用 v8::Global 以便在不同作用域中存储模块
-    module_cache: BTreeMap<PathBuf, v8::Global<v8::Module>>,
+    // 入口模块的路径, 用于 import.meta.main
+    main_module_path: Option<PathBuf>,
+}
 
-    // 内置模块存储 - 按名称缓存内置模块（如 "fs"）
-    builtin_modules: BTreeMap<String, v8::Global<v8::Module>>,
+/// 模块名称解析的结果
+enum Resolution {
+    Builtin,       // 内置模块，由 load_builtin_module 加载（或抛出还不支持的异常）
+    File(PathBuf), // 文件规范化后的绝对路径
 }
 
 impl ModuleLoader {
-    /// 初始化 ModuleLoader，将 ModuleLoader 注入到 V8 隔离区的 1 位置的插槽中
+    /// 创建 ModuleLoader
     ///
-    /// # 参数
-    /// - `isolate`: V8 隔离区
+    /// ModuleLoader 由 JsRuntime 持有（Box 保证地址稳定），指针存放在 V8 隔离区的 1 位置的插槽中，
+    /// 供模块回调使用；它持有的 Global 句柄必须在隔离区销毁之前释放
     ///
-    /// # 返回
-    /// 返回一个静态可变引用（使用不安全代码）
-    pub fn init_and_inject(isolate: &mut v8::Isolate) -> &'static mut ModuleLoader {
-        // Box::into_raw 获取原始指针，手动管理内存，编译器不会自动管理
-        let module_loader = Box::into_raw(Box::new(Self {
-            id_to_path_map: BTreeMap::new(),
-            module_cache: BTreeMap::new(),
-            builtin_modules: BTreeMap::new(),
-        }));
-
-        // set_data() 允许你将任意数据与 V8 Isolate 关联起来，这些数据可以在后续的回调函数、JavaScript 执行过程中访问
-        isolate.set_data(1, module_loader as *mut _);
+    /// # 参数
+    /// - `builtins`: 可以 import 的内置模块
+    pub fn new(builtins: Vec<BuiltinModule>) -> Self {
+        // 同名的内置模块以后注册的为准
+        let builtin_registry = builtins
+            .into_iter()
+            .map(|builtin| (builtin.name.clone(), builtin))
+            .collect();
 
-        unsafe { &mut *module_loader }
+        Self {
+            modules: ModuleRegistry::default(),
+            builtin_registry,
+            commonjs_modules: BTreeMap::new(),
+            main_module_path: None,
+        }
     }
 
     /// 编译脚本代码为 V8 模块
@@ -52,12 +74,12 @@ impl ModuleLoader {
     /// - `resource_name_str`: 资源名称（用于错误信息和调试）
     ///
     /// # 返回
-    /// 返回编译后的模块和其唯一标识哈希值的元组
+    /// 返回编译后的模块，语法错误时 V8 已经抛出 SyntaxError
     fn compile_script_module<'s>(
         scope: &mut v8::HandleScope<'s>,
         code: &str,          // JS 源代码
         resource_path: &str, // 资源路径
-    ) -> Option<(v8::Local<'s, v8::Module>, i32)> {
+    ) -> Option<v8::Local<'s, v8::Module>> {
         // 创建源代码字符串
         let source = v8::String::new(scope, code)?;
         // 文件 URL 前缀
@@ -88,70 +110,270 @@ impl ModuleLoader {
         let mut source = v8::script_compiler::Source::new(source, Some(&script_origin)); // 创建 js 代码源对象
 
         // 编译为 ES6 模块
-        v8::script_compiler::compile_module(scope, &mut source).map(|module| {
-            let hash_id: i32 = module.get_identity_hash().into(); // 获取模块唯一哈希 ID
-            (module, hash_id) // 返回模块和 ID
-        })
+        v8::script_compiler::compile_module(scope, &mut source)
+    }
+
+    /// 编译经典脚本（非 ES 模块）
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `code`: JS 源代码
+    /// - `resource_path`: 资源路径（用于错误信息, 脚本中的动态 import() 基于它所在的目录解析）
+    fn compile_classic_script<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        code: &str,
+        resource_path: &str,
+    ) -> Option<v8::Local<'s, v8::Script>> {
+        let source = v8::String::new(scope, code)?;
+        let resource_path = v8::String::new(scope, resource_path)?.into();
+
+        // 创建脚本来源信息, 与模块相同, 只是不作为 esm 模块编译
+        let script_origin = v8::ScriptOrigin::new(
+            scope,
+            resource_path, // 资源路径
+            0,             // 行偏移
+            0,             // 列偏移
+            false,         // 是否是共享代码
+            0,             // 脚本 ID
+            None,          // sourcemap URL
+            false,         // 是否是 opaque
+            false,         // 是否是 wasm
+            false,         // 是否是 esm 模块
+            None,          // 主机定义的选项
+        );
+
+        v8::Script::compile(scope, source, Some(&script_origin))
+    }
+
+    /// 编译模块并注册到模块注册表
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `absolute_path`: 模块的绝对路径（可以是不存在于磁盘上的虚拟路径）
+    /// - `content`: 模块的源代码
+    fn compile_and_cache_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        absolute_path: &Path,
+        content: &str,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        let resource_path = absolute_path.to_str().unwrap_or("unknown.js");
+
+        // 编译模块, 语法错误时 V8 已经抛出 SyntaxError
+        let module = Self::compile_script_module(scope, content, resource_path)?;
+
+        // 注册模块, 依赖解析时根据它查询导入者的路径
+        let source = Some(content.to_string());
+        self.modules
+            .insert(scope, module, absolute_path, ModuleKind::Source, source);
+
+        Some(module)
     }
 
     /// 核心函数：获取或编译模块（使用缓存或按需编译）
     ///
+    /// 只负责编译，实例化由调用方对根模块统一进行（依赖会在实例化时通过 resolve_module_callback 解析）
+    ///
     /// # 参数
     /// - `scope`: V8 作用域
     /// - `absolute_path`: 模块的绝对路径
     ///
     /// # 返回
-    /// 返回本地作用域中的模块引用
+    /// 返回本地作用域中的模块引用，失败时已在 JS 端抛出异常
     fn get_or_compile_module<'s>(
         &mut self,
         scope: &mut v8::HandleScope<'s>,
         absolute_path: &Path, // 绝对路径
     ) -> Option<v8::Local<'s, v8::Module>> {
-        let absolute_path_buf = absolute_path.to_path_buf();
-
-        // 检查模块是否已在缓存中
-        if let Some(global_module) = self.module_cache.get(&absolute_path_buf) {
-            // 从全局缓存返回本地引用, v8::Local::new 用于在不同的 V8 作用域(Scope) 之间传递 JavaScript 值
-            return Some(v8::Local::new(scope, global_module));
+        // 检查模块是否已经加载过
+        if let Some(record) = self.modules.get_by_path(absolute_path) {
+            // 从全局句柄返回本地引用, v8::Local::new 用于在不同的 V8 作用域(Scope) 之间传递 JavaScript 值
+            return Some(record.module(scope));
         }
 
         // 模块不在缓存中，读取并编译
-        let content = match fs::read_to_string(absolute_path) {
-            Ok(content) => content,
-            Err(e) => {
-                eprintln!("Error reading file '{}': {}", absolute_path.display(), e); // 错误日志
-                return None;
-            }
-        };
+        let content = read_source(scope, absolute_path)?;
+        self.compile_and_cache_module(scope, absolute_path, &content)
+    }
 
-        let resource_path = absolute_path.to_str().unwrap_or("unknown.js");
+    /// 获取或创建 JSON 模块
+    ///
+    /// 文件内容按 JSON 解析后作为合成模块的 default 导出，内容不是合法的 JSON 时抛出 SyntaxError
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `absolute_path`: JSON 文件的绝对路径
+    fn get_or_create_json_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        absolute_path: &Path,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        if let Some(record) = self.modules.get_by_path(absolute_path) {
+            return Some(record.module(scope));
+        }
 
-        if let Some((module, hash_id)) =
-            // 编译模块
-            Self::compile_script_module(scope, &content, resource_path)
-        {
-            // 缓存 ID 到路径的映射（在依赖解析时需要）
-            self.id_to_path_map
-                .insert(hash_id, absolute_path_buf.clone());
-
-            // 实例化模块（重要步骤）
-            if module
-                .instantiate_module(scope, resolve_module_callback) // 实例化模块，指定依赖解析函数
-                .is_none()
-            {
-                eprintln!("错误: 实例化模块失败: {}", absolute_path.display());
-                return None;
-            }
+        let value = read_json(scope, absolute_path)?;
+        let kind = ModuleKind::Json(v8::Global::new(scope, value));
+        self.create_synthetic_module(scope, absolute_path, &[], kind, None)
+    }
 
-            // v8::Global 用于在 rust 中持有对 JavaScript 对象的持久引用, 以便于在不同作用域中存储模块
-            let global_module = v8::Global::new(scope, module);
-            // 缓存模块
-            self.module_cache.insert(absolute_path_buf, global_module);
+    /// 获取或创建被 import 的 CommonJS 模块
+    ///
+    /// 这里只创建合成模块，CommonJS 模块在 ES 模块图执行到它时（synthetic_module_evaluation_steps 中）才执行，
+    /// 与 ES 模块的执行顺序一致。default 导出为 module.exports，从源代码中识别出的属性同时作为命名导出
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `absolute_path`: CommonJS 模块的绝对路径
+    fn get_or_create_commonjs_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        absolute_path: &Path,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        if let Some(record) = self.modules.get_by_path(absolute_path) {
+            return Some(record.module(scope));
+        }
+
+        let source = read_source(scope, absolute_path)?;
+        let named_exports = commonjs::detect_export_names(&source);
+        let kind = ModuleKind::CommonJs(named_exports.clone());
+        self.create_synthetic_module(scope, absolute_path, &named_exports, kind, Some(source))
+    }
+
+    /// 创建以一个值作为 default 导出的合成模块（JSON 模块、被 import 的 CommonJS 模块）并注册
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `absolute_path`: 模块的绝对路径
+    /// - `named_exports`: default 以外的导出名称
+    /// - `kind`: 模块的类型，执行时根据它确定导出的值
+    /// - `source`: 模块的源代码
+    fn create_synthetic_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        absolute_path: &Path,
+        named_exports: &[String],
+        kind: ModuleKind,
+        source: Option<String>,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        let module_name = v8::String::new(scope, &absolute_path.to_string_lossy())?;
+        let export_names = std::iter::once("default")
+            .chain(named_exports.iter().map(String::as_str))
+            .map(|export_name| v8::String::new(scope, export_name))
+            .collect::<Option<Vec<_>>>()?;
+        let module = v8::Module::create_synthetic_module(
+            scope,
+            module_name,
+            &export_names,
+            synthetic_module_evaluation_steps,
+        );
 
-            Some(module)
-        } else {
-            eprintln!("错误: 编译模块失败: {}", absolute_path.display());
-            None // 编译失败
+        self.modules
+            .insert(scope, module, absolute_path, kind, source);
+
+        Some(module)
+    }
+
+    /// 加载并执行 CommonJS 模块，已加载（或正在加载）的模块直接返回缓存的 module.exports
+    ///
+    /// module 对象在执行前放入缓存，循环 require 时得到尚未执行完成的 module.exports，
+    /// 执行失败时从缓存中移除，之后的 require 会重新执行
+    fn load_commonjs_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        absolute_path: &Path,
+    ) -> Option<v8::Local<'s, v8::Value>> {
+        if let Some(module) = self.commonjs_modules.get(absolute_path) {
+            let module = v8::Local::new(scope, module);
+            return commonjs::get_exports(scope, module);
+        }
+
+        let content = read_source(scope, absolute_path)?;
+        let module = commonjs::create_module_object(scope, absolute_path)?;
+        self.commonjs_modules
+            .insert(absolute_path.to_path_buf(), v8::Global::new(scope, module));
+
+        if commonjs::execute_module(scope, module, &content, absolute_path).is_none() {
+            self.commonjs_modules.remove(absolute_path);
+            return None;
+        }
+        commonjs::get_exports(scope, module)
+    }
+
+    /// 通过 require() 加载 JSON 文件，解析后的值同样放入 CommonJS 模块缓存
+    fn load_json_for_require<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        absolute_path: &Path,
+    ) -> Option<v8::Local<'s, v8::Value>> {
+        let value = read_json(scope, absolute_path)?;
+        let module = commonjs::create_module_object(scope, absolute_path)?;
+        commonjs::set_exports(scope, module, value)?;
+        self.commonjs_modules
+            .insert(absolute_path.to_path_buf(), v8::Global::new(scope, module));
+        Some(value)
+    }
+
+    /// require() 的实现
+    ///
+    /// 解析规则与 import 相同（exports 中使用 require 条件），按文件类型加载：
+    /// - CommonJS 模块返回 module.exports
+    /// - JSON 文件返回解析后的值
+    /// - ES 模块同步执行后返回命名空间，含有顶层 await 时抛出异常
+    /// - 内置模块返回 default 导出
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `specifier_str`: require 的模块名称
+    /// - `referrer_path`: 调用 require 的模块路径
+    ///
+    /// # 返回
+    /// 加载失败时返回 None，异常已在 JS 端抛出
+    pub(crate) fn require<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        specifier_str: &str,
+        referrer_path: &Path,
+    ) -> Option<v8::Local<'s, v8::Value>> {
+        let resolved_path =
+            match self.resolve_specifier(specifier_str, referrer_path, REQUIRE_CONDITIONS) {
+                Ok(Resolution::File(resolved_path)) => resolved_path,
+                // 内置模块返回 default 导出（没有时返回命名空间）
+                Ok(Resolution::Builtin) => {
+                    let module = self.load_builtin_module(scope, specifier_str)?;
+                    let namespace = evaluate_for_require(scope, module)?;
+                    let default_name = v8::String::new(scope, "default")?;
+                    let default_export = namespace
+                        .get(scope, default_name.into())
+                        .filter(|default_export| !default_export.is_undefined());
+                    return default_export.or(Some(namespace.into()));
+                }
+                Err(error) => {
+                    throw_error_with_code(scope, &error.message, error.code);
+                    return None;
+                }
+            };
+
+        // 已经通过 require 加载过的模块（包括 JSON 文件）
+        if let Some(module) = self.commonjs_modules.get(&resolved_path) {
+            let module = v8::Local::new(scope, module);
+            return commonjs::get_exports(scope, module);
+        }
+
+        let is_json = resolved_path
+            .extension()
+            .is_some_and(|extension| extension == "json");
+        match is_commonjs(&resolved_path) {
+            Ok(true) => self.load_commonjs_module(scope, &resolved_path),
+            Ok(false) if is_json => self.load_json_for_require(scope, &resolved_path),
+            Ok(false) => {
+                let module = self.get_or_compile_module(scope, &resolved_path)?;
+                evaluate_for_require(scope, module).map(Into::into)
+            }
+            Err(error) => {
+                throw_error_with_code(scope, &error.message, error.code);
+                None
+            }
         }
     }
 
@@ -160,77 +382,505 @@ impl ModuleLoader {
     /// # 参数
     /// - `scope`: V8 作用域
     /// - `path_str`: 路径字符串（可以是相对路径或绝对路径）
+    ///
+    /// # 返回
+    /// 返回编译后（尚未实例化）的入口模块
     pub fn create_first_module<'s>(
         &mut self,
         scope: &mut v8::HandleScope<'s>,
         path_str: &str, // 路径字符串
-    ) -> Option<v8::Local<'s, v8::Module>> {
+    ) -> Result<v8::Local<'s, v8::Module>, JsError> {
         // 规范化路径为绝对路径
         let path = Path::new(path_str); // 创建路径对象
-        let absolute_path = match fs::canonicalize(path) {
-            Ok(p) => p, // 成功规范化
-            Err(e) => {
-                eprintln!("错误: 规范化入口点路径 '{}' 失败: {}", path_str, e);
-                return None;
-            }
-        };
+        let absolute_path = fs::canonicalize(path)
+            .map_err(|e| JsError::Load(format!("规范化入口点路径 '{}' 失败: {}", path_str, e)))?;
+        self.main_module_path = Some(absolute_path.clone());
 
-        self.get_or_compile_module(scope, &absolute_path) // 获取或编译模块
+        // 获取或编译模块, 捕获编译时抛出的异常
+        let tc_scope = &mut v8::TryCatch::new(scope);
+        self.get_or_compile_module(tc_scope, &absolute_path)
+            .ok_or_else(|| JsError::Compile(JsException::from_try_catch(tc_scope)))
     }
 
-    /// 初始化内置 API, 例如 fs
+    /// 根据内存中的源代码创建入口模块
+    ///
+    /// 模块使用虚拟路径（相对路径基于当前工作目录）, 其中的相对 import 基于虚拟路径所在的目录解析
     ///
-    /// 创建一个合成的 V8 API，由 Rust 代码实现
-    fn init_builtin_module(
-        scope: &mut v8::HandleScope<'_>,
-        specifier_str: &str, // 模块名称
-    ) -> v8::Global<v8::Module> {
-        let fs_module_name = v8::String::new(scope, specifier_str).unwrap(); // 模块名称字符串
-        let export_names = &[v8::String::new(scope, "default").unwrap()]; // 导出名称
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `name`: 模块的虚拟路径, 例如 "main.js"
+    /// - `code`: 模块的源代码
+    ///
+    /// # 返回
+    /// 返回编译后（尚未实例化）的入口模块
+    pub fn create_inline_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        name: &str,
+        code: &str,
+    ) -> Result<v8::Local<'s, v8::Module>, JsError> {
+        let virtual_path = virtual_path(name)?;
+        self.main_module_path = Some(virtual_path.clone());
+
+        let tc_scope = &mut v8::TryCatch::new(scope);
+        self.compile_and_cache_module(tc_scope, &virtual_path, code)
+            .ok_or_else(|| JsError::Compile(JsException::from_try_catch(tc_scope)))
+    }
+
+    /// 根据内存中的源代码创建经典脚本
+    ///
+    /// 脚本的虚拟路径为当前工作目录下的 "[eval]", 其中的动态 import() 基于当前工作目录解析
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `code`: 脚本的源代码
+    pub fn create_inline_script<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        code: &str,
+    ) -> Result<v8::Local<'s, v8::Script>, JsError> {
+        let virtual_path = virtual_path("[eval]")?;
+        let resource_path = virtual_path.to_str().unwrap_or("[eval]");
+
+        let tc_scope = &mut v8::TryCatch::new(scope);
+        Self::compile_classic_script(tc_scope, code, resource_path)
+            .ok_or_else(|| JsError::Compile(JsException::from_try_catch(tc_scope)))
+    }
+
+    /// 初始化内置模块, 例如 fs
+    ///
+    /// 由 Rust 实现的内置模块创建为合成模块，导出的值在模块执行时由初始化回调构造；
+    /// 由 JS 源代码实现的内置模块编译为普通的 ES 模块
+    ///
+    /// # 返回
+    /// 返回内置模块，编译失败时已在 JS 端抛出异常
+    fn init_builtin_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        builtin: &BuiltinModule,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        let module_name = v8::String::new(scope, &builtin.name)?; // 模块名称字符串
+
+        let exports = match &builtin.source {
+            BuiltinSource::Exports(exports) => exports,
+            // 以虚拟的键作为路径编译和缓存, 这样它的 import 也可以通过 resolve_module_callback 解析
+            BuiltinSource::Source(source) => {
+                return self.compile_and_cache_module(scope, &builtin_key(&builtin.name), source);
+            }
+        };
+
+        // 导出名称, 没有声明 default 时额外导出包含所有导出的 default 对象
+        let mut export_names = exports
+            .iter()
+            .map(|(export_name, _)| v8::String::new(scope, export_name))
+            .collect::<Option<Vec<_>>>()?;
+        if !has_default_export(exports) {
+            export_names.push(v8::String::new(scope, "default")?);
+        }
 
         // 创建模块（由 Rust 代码实现的模块）
         let module = v8::Module::create_synthetic_module(
             scope,
-            fs_module_name, // 模块名
-            export_names,   // 导出项名
-            |context: v8::Local<'_, v8::Context>, module: v8::Local<'_, v8::Module>| {
-                // 初始化回调
-                let mut scope = unsafe { CallbackScope::new(context) }; // 从上下文创建作用域
-                let default_export_name = v8::String::new(&mut scope, "default").unwrap(); // "default" 字符串
-
-                let fs_module = create_fs(&mut scope); // 创建文件系统模块
-                let value = fs_module.new_instance(&mut scope).unwrap(); // 创建模块实例
-
-                // 设置 default 导出
-                let result = module.set_synthetic_module_export(
-                    &mut scope,
-                    default_export_name,
-                    value.into(),
-                );
-
-                result.map(|result| v8::Boolean::new(&mut scope, result).into())
-                // 返回布尔值
-            },
+            module_name,   // 模块名
+            &export_names, // 导出项名
+            synthetic_module_evaluation_steps,
         );
 
-        v8::Global::new(scope, module) // 包装为 Global
+        // 以虚拟的键作为路径注册, 初始化回调中根据名称找到要导出的值
+        let kind = ModuleKind::Builtin(builtin.name.clone());
+        self.modules
+            .insert(scope, module, &builtin_key(&builtin.name), kind, None);
+
+        Some(module)
+    }
+
+    /// 解析并加载 import 的模块，静态 import 与动态 import() 共用这段逻辑
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `specifier_str`: import 导入的模块路径
+    /// - `referrer_path`: 导入者的文件路径，相对路径基于它所在的目录解析
+    /// - `module_type`: import 属性指定的模块类型
+    pub fn resolve_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        specifier_str: &str,
+        referrer_path: &Path,
+        module_type: ModuleType,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        let resolved = match self.resolve_specifier(specifier_str, referrer_path, IMPORT_CONDITIONS)
+        {
+            Ok(Resolution::File(resolved_path)) => Ok(resolved_path),
+            Ok(Resolution::Builtin) => {
+                // 内置模块不是 JSON 模块
+                if module_type != ModuleType::JavaScript {
+                    let error = module_type.incompatible(specifier_str);
+                    throw_error_with_code(scope, &error.message, error.code);
+                    return None;
+                }
+                return self.load_builtin_module(scope, specifier_str);
+            }
+            Err(error) => Err(error),
+        };
+
+        self.load_resolved_module(scope, resolved, module_type)
+    }
+
+    /// 解析模块名称，只确定要加载的模块，不读取或编译文件
+    ///
+    /// - 内置模块只能 import 其他内置模块
+    /// - `#` 开头的名称按所属包的 package.json 的 imports 解析
+    /// - `file:` URL（例如 import.meta.resolve 的结果）转换为绝对路径后按文件解析
+    /// - 不以 . 或 / 开头的是包名: 内置模块优先, 其次从 node_modules 中查找
+    /// - 相对路径和绝对路径, 找不到文件时错误信息中列出尝试过的候选路径
+    ///
+    /// # 参数
+    /// - `specifier_str`: 模块名称
+    /// - `referrer_path`: 导入者的文件路径
+    /// - `conditions`: exports、imports 中支持的条件
+    fn resolve_specifier(
+        &self,
+        specifier_str: &str,
+        referrer_path: &Path,
+        conditions: &[&str],
+    ) -> Result<Resolution, ResolveError> {
+        let name = builtin_name(specifier_str); // 注册表中的名称
+        let is_builtin =
+            specifier_str.starts_with("node:") || self.builtin_registry.contains_key(name);
+
+        if is_builtin_key(referrer_path) {
+            if is_builtin || is_node_builtin(name) {
+                return Ok(Resolution::Builtin);
+            }
+            return Err(ResolveError::not_found(format!(
+                "Cannot find module '{}' imported from {}: builtin modules can only import other builtin modules",
+                specifier_str,
+                referrer_path.display()
+            )));
+        }
+
+        if specifier_str.starts_with('#') {
+            return resolve_package_import(specifier_str, referrer_path, conditions)
+                .map(Resolution::File);
+        }
+
+        if specifier_str.starts_with("file://") {
+            let path = file_url_to_path(specifier_str);
+            return resolve_path(&path.to_string_lossy(), referrer_path, conditions)
+                .map(Resolution::File);
+        }
+
+        if specifier_str.starts_with('.') || specifier_str.starts_with('/') {
+            return resolve_path(specifier_str, referrer_path, conditions).map(Resolution::File);
+        }
+
+        // 已注册的内置模块和 node: 前缀的名称
+        if is_builtin {
+            return Ok(Resolution::Builtin);
+        }
+
+        // node_modules 中也找不到的 Node.js 内置模块名称由 load_builtin_module 抛出还不支持的异常
+        match resolve_package(specifier_str, referrer_path, conditions) {
+            Err(error) if error.code == "ERR_MODULE_NOT_FOUND" && is_node_builtin(name) => {
+                Ok(Resolution::Builtin)
+            }
+            resolved => resolved.map(Resolution::File),
+        }
+    }
+
+    /// import.meta.resolve() 的实现
+    ///
+    /// # 返回
+    /// 文件返回 file:// URL，Node.js 内置模块返回带 `node:` 前缀的名称，其他内置模块返回原名称
+    fn resolve_url(
+        &self,
+        specifier_str: &str,
+        referrer_path: &Path,
+    ) -> Result<String, ResolveError> {
+        match self.resolve_specifier(specifier_str, referrer_path, IMPORT_CONDITIONS)? {
+            Resolution::File(resolved_path) => Ok(path_to_file_url(&resolved_path)),
+            Resolution::Builtin => {
+                let name = builtin_name(specifier_str);
+                if is_node_builtin(name) {
+                    Ok(format!("node:{}", name))
+                } else if self.builtin_registry.contains_key(name) {
+                    Ok(specifier_str.to_string())
+                } else {
+                    Err(ResolveError::not_found(format!(
+                        "Cannot find module '{}' imported from {}",
+                        specifier_str,
+                        referrer_path.display()
+                    )))
+                }
+            }
+        }
+    }
+
+    /// 加载解析得到的文件，解析失败或文件与模块类型不一致时抛出带有错误码的异常
+    fn load_resolved_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        resolved: Result<PathBuf, ResolveError>,
+        module_type: ModuleType,
+    ) -> Option<v8::Local<'s, v8::Module>> {
+        let resolved = resolved.and_then(|resolved_path| {
+            module_type.check_path(&resolved_path)?;
+            Ok(resolved_path)
+        });
+
+        // CommonJS 模块通过合成模块导出 module.exports
+        let resolved = resolved.and_then(|resolved_path| {
+            let is_commonjs = is_commonjs(&resolved_path)?;
+            Ok((resolved_path, is_commonjs))
+        });
+
+        match (resolved, module_type) {
+            (Ok((resolved_path, true)), ModuleType::JavaScript) => {
+                self.get_or_create_commonjs_module(scope, &resolved_path)
+            }
+            (Ok((resolved_path, false)), ModuleType::JavaScript) => {
+                self.get_or_compile_module(scope, &resolved_path)
+            }
+            (Ok((resolved_path, _)), ModuleType::Json) => {
+                self.get_or_create_json_module(scope, &resolved_path)
+            }
+            (Err(error), _) => {
+                throw_error_with_code(scope, &error.message, error.code);
+                None
+            }
+        }
     }
 
     /// 加载内置模块（如 "fs"）
     ///
-    /// 如果模块未缓存则初始化，否则从缓存获取
+    /// 如果模块未缓存则初始化，否则从缓存获取，没有注册的内置模块抛出异常。
+    /// `node:` 前缀的 Node.js 内置模块与不带前缀的名称共用同一个模块
     pub fn load_builtin_module<'s>(
         &mut self,
         scope: &mut v8::HandleScope<'s>,
         specifier_str: &str, // import 导入的模块名称
     ) -> Option<v8::Local<'s, v8::Module>> {
-        let module = self
-            .builtin_modules
-            .entry(specifier_str.to_string()) // 从字典获取或插入
-            .or_insert_with(|| Self::init_builtin_module(scope, specifier_str)); // 不存在则初始化
+        let name = builtin_name(specifier_str); // 注册表中的名称
+        if let Some(record) = self.modules.get_by_path(&builtin_key(name)) {
+            return Some(record.module(scope));
+        }
+
+        let Some(builtin) = self.builtin_registry.get(name).cloned() else {
+            // 区分还没有实现的 Node.js 内置模块和不存在的模块
+            let message = if is_node_builtin(name) {
+                format!(
+                    "Node.js builtin module '{}' is not supported by this runtime",
+                    specifier_str
+                )
+            } else {
+                format!("Cannot find module '{}'", specifier_str)
+            };
+            throw_error(scope, &message);
+            return None;
+        };
+
+        // 不存在则初始化, 初始化时注册到模块注册表
+        self.init_builtin_module(scope, &builtin)
+    }
+
+    /// 查询已加载模块的信息
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `path`: 模块的绝对路径（内置模块为 builtin_key 返回的键）
+    pub(crate) fn module_info(
+        &self,
+        scope: &mut v8::HandleScope<()>,
+        path: &Path,
+    ) -> Option<ModuleInfo> {
+        self.modules.info(scope, path)
+    }
+}
+
+/// 合成模块的初始化回调
+///
+/// 模块执行时调用，JSON 模块和被 import 的 CommonJS 模块导出对应的值；
+/// 内置模块根据模块注册表中的名称找到它的定义，构造并设置它的每个导出
+fn synthetic_module_evaluation_steps<'s>(
+    context: v8::Local<'s, v8::Context>,
+    module: v8::Local<'s, v8::Module>,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let mut scope = unsafe { CallbackScope::new(context) }; // 从上下文创建作用域
+
+    let state_ptr = scope.get_data(1); // 获取 ModuleLoader 指针
+    if state_ptr.is_null() {
+        eprintln!("错误: 在 synthetic_module_evaluation_steps 中的 ModuleLoader 为空 ");
+        return None;
+    }
+    let module_loader = unsafe { &mut *(state_ptr as *mut ModuleLoader) };
+
+    // 根据模块句柄（而不是可能重复的 identity hash）找到模块的记录
+    let record = module_loader.modules.get_by_module(module)?;
+    let name = match &record.kind {
+        ModuleKind::Builtin(name) => name,
+        ModuleKind::Json(value) => {
+            let value = v8::Local::new(&mut scope, value);
+            set_value_exports(&mut scope, module, value, &[])?;
+            return Some(v8::Boolean::new(&mut scope, true).into());
+        }
+        // CommonJS 模块在这里（ES 模块图的执行阶段）才执行, 抛出的异常使模块进入 Errored 状态
+        ModuleKind::CommonJs(named_exports) => {
+            let named_exports = named_exports.clone();
+            let path = record.path.clone();
+            let exports = module_loader.load_commonjs_module(&mut scope, &path)?;
+            set_value_exports(&mut scope, module, exports, &named_exports)?;
+            return Some(v8::Boolean::new(&mut scope, true).into());
+        }
+        ModuleKind::Source => return None, // 由源代码编译的模块不是合成模块
+    };
+
+    let builtin = module_loader.builtin_registry.get(name)?;
+    let BuiltinSource::Exports(exports) = &builtin.source else {
+        return None;
+    };
+
+    let default_object = v8::Object::new(&mut scope); // 包含所有导出的 default 对象
+
+    for (export_name, export) in exports {
+        let export_name = v8::String::new(&mut scope, export_name)?;
+        let value = match export {
+            BuiltinExport::Function(callback) => v8::Function::builder_raw(*callback)
+                .build(&mut scope)?
+                .into(),
+            BuiltinExport::Value(builder) => builder(&mut scope),
+        };
+
+        // 设置导出, 同一个值同时作为 default 对象的属性
+        module.set_synthetic_module_export(&mut scope, export_name, value)?;
+        default_object.set(&mut scope, export_name.into(), value)?;
+    }
+
+    // 没有声明 default 时, default 导出为包含所有导出的对象（兼容 import fs from "fs"）
+    if !has_default_export(exports) {
+        let default_name = v8::String::new(&mut scope, "default")?;
+        module.set_synthetic_module_export(&mut scope, default_name, default_object.into())?;
+    }
+
+    Some(v8::Boolean::new(&mut scope, true).into()) // 返回布尔值
+}
+
+/// 设置以一个值作为 default 导出的合成模块的导出
+///
+/// 命名导出取自该值的同名属性，值不是对象时命名导出都为 undefined
+fn set_value_exports(
+    scope: &mut v8::HandleScope,
+    module: v8::Local<v8::Module>,
+    value: v8::Local<v8::Value>,
+    named_exports: &[String],
+) -> Option<()> {
+    let default_name = v8::String::new(scope, "default")?;
+    module.set_synthetic_module_export(scope, default_name, value)?;
+
+    let object = value.try_cast::<v8::Object>().ok();
+    for export_name in named_exports {
+        let export_name = v8::String::new(scope, export_name)?;
+        let export = match object {
+            Some(object) => object.get(scope, export_name.into())?,
+            None => v8::undefined(scope).into(),
+        };
+        module.set_synthetic_module_export(scope, export_name, export)?;
+    }
+    Some(())
+}
+
+/// 内置模块是否声明了 default 导出
+fn has_default_export(exports: &[(String, BuiltinExport)]) -> bool {
+    exports
+        .iter()
+        .any(|(export_name, _)| export_name == "default")
+}
+
+/// 读取模块的源代码，失败时抛出异常
+fn read_source(scope: &mut v8::HandleScope, absolute_path: &Path) -> Option<String> {
+    match fs::read_to_string(absolute_path) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            let message = format!("Cannot read module '{}': {}", absolute_path.display(), e);
+            throw_error(scope, &message);
+            None
+        }
+    }
+}
+
+/// 读取并解析 JSON 文件，内容不是合法的 JSON 时 V8 已经抛出 SyntaxError
+fn read_json<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    absolute_path: &Path,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let content = read_source(scope, absolute_path)?;
+    let content = v8::String::new(scope, &content)?;
+    v8::json::parse(scope, content)
+}
+
+/// 同步实例化并执行被 require 的 ES 模块（或内置模块）
+///
+/// # 返回
+/// 返回模块的命名空间，执行失败或含有顶层 await 时抛出异常并返回 None
+fn evaluate_for_require<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    module: v8::Local<'s, v8::Module>,
+) -> Option<v8::Local<'s, v8::Object>> {
+    if module.get_status() == v8::ModuleStatus::Uninstantiated {
+        module.instantiate_module(scope, resolve_module_callback)?;
+    }
 
-        Some(v8::Local::new(scope, &*module))
+    // 执行失败时模块进入 Errored 状态, evaluate 返回的 Promise 被 reject
+    let evaluation = module.evaluate(scope)?;
+    if let Ok(evaluation) = evaluation.try_cast::<v8::Promise>() {
+        match evaluation.state() {
+            v8::PromiseState::Rejected => {
+                // 异常由 require 抛出, 不算作未处理的 rejection; V8 已经通知过 rejection, 要从跟踪器中移除
+                evaluation.mark_as_handled();
+                forget_rejection(scope, evaluation);
+                let exception = evaluation.result(scope);
+                scope.throw_exception(exception);
+                return None;
+            }
+            v8::PromiseState::Pending => {
+                let message = "require() cannot be used on an ES module that uses top-level await, use import() instead";
+                throw_error_with_code(scope, message, "ERR_REQUIRE_ASYNC_MODULE");
+                return None;
+            }
+            v8::PromiseState::Fulfilled => {}
+        }
     }
+
+    Some(module.get_module_namespace().cast::<v8::Object>())
+}
+
+/// 内置模块在模块注册表中的键
+///
+/// Node.js 内置模块为 `node:fs` 这样的名称，其他内置模块加上 `zjs:` 前缀（例如 `zjs:app:db`），
+/// 不会与文件的绝对路径冲突，也不会被当作相对路径基于当前工作目录解析
+pub(crate) fn builtin_key(name: &str) -> PathBuf {
+    if is_node_builtin(name) {
+        PathBuf::from(format!("node:{}", name))
+    } else {
+        PathBuf::from(format!("zjs:{}", name))
+    }
+}
+
+/// 路径是否为内置模块的键
+fn is_builtin_key(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|path| path.starts_with("node:") || path.starts_with("zjs:"))
+}
+
+/// 将内存中源代码的名称转换为虚拟的绝对路径, 相对路径基于当前工作目录
+fn virtual_path(name: &str) -> Result<PathBuf, JsError> {
+    let path = Path::new(name);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+
+    let current_dir =
+        env::current_dir().map_err(|e| JsError::Load(format!("获取当前工作目录失败: {}", e)))?;
+    Ok(current_dir.join(path))
 }
 
 /// 模块依赖解析回调函数
@@ -239,7 +889,7 @@ impl ModuleLoader {
 pub fn resolve_module_callback<'s>(
     context: v8::Local<'s, v8::Context>,
     specifier: v8::Local<'s, v8::String>, // import 其他导入的模块路径
-    _import_assertions: v8::Local<'s, v8::FixedArray>, // import 断言（未使用）
+    import_attributes: v8::Local<'s, v8::FixedArray>, // import 属性, 例如 with { type: "json" }
     referrer: v8::Local<'s, v8::Module>,  // 当前的文件引用
 ) -> Option<v8::Local<'s, v8::Module>> {
     let mut scope = unsafe { v8::CallbackScope::new(context) }; // 创建作用域
@@ -252,35 +902,205 @@ pub fn resolve_module_callback<'s>(
     let module_loader = unsafe { &mut *(state_ptr as *mut ModuleLoader) }; // 转换为引用
     let specifier_str = specifier.to_rust_string_lossy(&mut scope); // 模块路径字符串
 
-    // 判断是否为内置模块（不含路径分隔符）, 如果是内置模块则加载内置模块
-    if !specifier_str.starts_with('.') && !specifier_str.starts_with('/') {
-        return module_loader.load_builtin_module(&mut scope, &specifier_str);
+    // 查询导入模块的编号和路径, 不是由 ModuleLoader 加载的模块无法确定相对路径的基准
+    let referrer_record = module_loader
+        .modules
+        .id_of(referrer)
+        .and_then(|id| Some((id, module_loader.modules.get(id)?.path.clone())));
+    let Some((referrer_id, referrer_path)) = referrer_record else {
+        let message = format!(
+            "Cannot resolve module '{}': the importing module is not loaded by this runtime",
+            specifier_str
+        );
+        throw_type_error(&mut scope, &message); // 返回 None 时必须有待处理的异常
+        return None;
+    };
+
+    // 静态 import 的属性按 (键, 值, 位置) 排列
+    let attributes = import_attributes_to_vec(&mut scope, import_attributes, 3);
+    let module_type = match ModuleType::from_attributes(&attributes) {
+        Ok(module_type) => module_type,
+        Err(error) => {
+            throw_error_with_code(&mut scope, &error.message, error.code);
+            return None;
+        }
+    };
+
+    let module =
+        module_loader.resolve_module(&mut scope, &specifier_str, &referrer_path, module_type)?;
+
+    // 记录依赖关系, 可以通过模块信息查询
+    if let Some(dependency_id) = module_loader.modules.id_of(module) {
+        module_loader
+            .modules
+            .add_dependency(referrer_id, dependency_id);
     }
+    Some(module)
+}
 
-    let referrer_id: i32 = referrer.get_identity_hash().into(); // 获取导入模块的 hash
-    let referrer_path = module_loader.id_to_path_map.get(&referrer_id)?; // 查询导入者路径
+/// 把 V8 传入的 import 属性转换为 (键, 值) 列表
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `import_attributes`: V8 传入的 import 属性
+/// - `entry_size`: 每个属性占用的元素个数（静态 import 为 3, 动态 import() 为 2）
+fn import_attributes_to_vec(
+    scope: &mut v8::HandleScope,
+    import_attributes: v8::Local<v8::FixedArray>,
+    entry_size: usize,
+) -> Vec<(String, String)> {
+    (0..import_attributes.length())
+        .step_by(entry_size)
+        .filter_map(|index| {
+            let key = import_attributes.get(scope, index)?.cast::<v8::String>();
+            let value = import_attributes
+                .get(scope, index + 1)?
+                .cast::<v8::String>();
+            Some((
+                key.to_rust_string_lossy(scope),
+                value.to_rust_string_lossy(scope),
+            ))
+        })
+        .collect()
+}
 
-    let referrer_dir = referrer_path.parent().unwrap_or(Path::new("")); // 导入者目录
-    let resolved_path_buf = referrer_dir.join(&specifier_str); // 解析路径
+/// 处理动态 import() 的回调函数
+///
+/// 返回的 Promise 先以一个已 resolve 的 Promise 开始，真正的加载在它的 then 回调中进行，
+/// 这样模块的加载和执行都发生在微任务中，由事件循环推进
+///
+/// # 参数
+/// - `scope`: V8 作用域，用于 GC 跟踪
+/// - `_host_defined_options`: 主机定义的选项
+/// - `resource_name`: 资源名称（发起 import() 的文件路径）
+/// - `specifier`: 模块标识符（import() 中的字符串）
+/// - `import_attributes`: import 属性（import() 第二个参数中的 with）
+pub fn host_import_module_dynamically_callback<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    _host_defined_options: v8::Local<'s, v8::Data>,
+    resource_name: v8::Local<'s, v8::Value>,
+    specifier: v8::Local<'s, v8::String>,
+    import_attributes: v8::Local<'s, v8::FixedArray>,
+) -> Option<v8::Local<'s, v8::Promise>> {
+    // 创建一个已 resolve 的 Promise 作为加载的起点
+    let resolver = v8::PromiseResolver::new(scope)?;
+    let ready_promise = resolver.get_promise(scope);
+    let undefined = v8::undefined(scope);
+    resolver.resolve(scope, undefined.into());
 
-    // 支持的文件扩展名
-    const EXTENSIONS: [&str; 2] = ["", "js"]; // 尝试原文件名和 .js 扩展
+    // 通过 data 把模块路径、导入者路径和 import 属性传给加载函数,
+    // 属性在加载函数中检查, 不支持的属性使 import() 返回的 Promise 被 reject
+    let mut import_info = vec![specifier.into(), resource_name];
+    for (key, value) in import_attributes_to_vec(scope, import_attributes, 2) {
+        import_info.push(v8::String::new(scope, &key)?.into());
+        import_info.push(v8::String::new(scope, &value)?.into());
+    }
+    let import_info = v8::Array::new_with_elements(scope, &import_info);
+    let import_loader = v8::Function::builder(dynamic_import_loader)
+        .data(import_info.into())
+        .build(scope)?;
 
-    EXTENSIONS
-        .iter()
-        .find_map(|extension| {
-            // 逐个尝试扩展名
-            let mut resolved_path_with_extension = resolved_path_buf.clone();
-            resolved_path_with_extension.set_extension(extension); // 添加扩展名
-            fs::canonicalize(&resolved_path_with_extension).ok() // 规范化路径
+    ready_promise.then(scope, import_loader)
+}
+
+/// 动态 import() 的加载函数，在微任务中执行
+///
+/// 解析、实例化并执行目标模块，返回模块命名空间（或等待顶层 await 完成后的命名空间），
+/// 加载失败时抛出异常，使 import() 返回的 Promise 被 reject
+fn dynamic_import_loader(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut return_value: v8::ReturnValue,
+) {
+    let import_info = args.data().cast::<v8::Array>(); // 取出 [specifier, resource_name, ...属性的键和值]
+    let specifier_str = import_info
+        .get_index(scope, 0)
+        .unwrap()
+        .to_rust_string_lossy(scope);
+    let referrer_str = import_info
+        .get_index(scope, 1)
+        .unwrap()
+        .to_rust_string_lossy(scope);
+
+    let state_ptr = scope.get_data(1); // 获取 ModuleLoader 指针
+    if state_ptr.is_null() {
+        eprintln!("错误: 在 dynamic_import_loader 中的 ModuleLoader state 为空 ");
+        return;
+    }
+    let module_loader = unsafe { &mut *(state_ptr as *mut ModuleLoader) };
+
+    let attributes = (2..import_info.length())
+        .step_by(2)
+        .filter_map(|index| {
+            let key = import_info.get_index(scope, index)?;
+            let value = import_info.get_index(scope, index + 1)?;
+            Some((
+                key.to_rust_string_lossy(scope),
+                value.to_rust_string_lossy(scope),
+            ))
         })
-        .and_then(|path| module_loader.get_or_compile_module(&mut scope, &path))
+        .collect::<Vec<_>>();
+    let module_type = match ModuleType::from_attributes(&attributes) {
+        Ok(module_type) => module_type,
+        Err(error) => {
+            throw_error_with_code(scope, &error.message, error.code);
+            return;
+        }
+    };
+
+    // 解析失败时异常已经被抛出, then 返回的 Promise 会因此被 reject
+    let Some(module) =
+        module_loader.resolve_module(scope, &specifier_str, Path::new(&referrer_str), module_type)
+    else {
+        return;
+    };
+
+    // 新编译的模块需要先实例化, 它的静态依赖会通过 resolve_module_callback 解析
+    if module.get_status() == v8::ModuleStatus::Uninstantiated
+        && module
+            .instantiate_module(scope, resolve_module_callback)
+            .is_none()
+    {
+        return;
+    }
+
+    // 执行模块, 失败时异常已经被抛出
+    let Some(evaluation) = module.evaluate(scope) else {
+        return;
+    };
+    let namespace = module.get_module_namespace(); // 模块导出的命名空间
+
+    // 含有顶层 await 的模块返回 Promise, 等它完成后再交出命名空间
+    match evaluation.try_cast::<v8::Promise>() {
+        Ok(evaluation_promise) => {
+            let namespace_mapper = v8::Function::builder(return_data)
+                .data(namespace)
+                .build(scope)
+                .unwrap();
+            if let Some(promise) = evaluation_promise.then(scope, namespace_mapper) {
+                return_value.set(promise.into());
+            }
+        }
+        Err(_) => return_value.set(namespace),
+    }
+}
+
+/// 直接返回函数 data 的回调，用于在 Promise 链中交出预先绑定的值
+fn return_data(
+    _scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut return_value: v8::ReturnValue,
+) {
+    return_value.set(args.data());
 }
 
 /// import.meta 对象初始化回调函数
 ///
-/// 当 JavaScript 代码访问 import.meta 时，V8 会调用此函数来初始化该对象
-/// 这里我们将模块的目录名称设为 import.meta.dirname
+/// 当 JavaScript 代码访问 import.meta 时，V8 会调用此函数来初始化该对象，设置：
+/// - `url`: 模块的 file:// URL（由 JS 源代码实现的内置模块为 `zjs:name` 这样的键）
+/// - `filename`、`dirname`: 模块的绝对路径及其所在的目录（只有文件模块才有）
+/// - `main`: 是否为入口模块
+/// - `resolve(specifier)`: 按 import 的规则解析模块名称，返回 URL
 ///
 /// extern "C" 是 Rust 中的外部函数接口 (FFI - Foreign Function Interface) 声明，用于与 C 语言 ABI (Application Binary Interface) 兼容、及防止名称被修改导致编译后 FFI 调用无法找到函数
 pub extern "C" fn host_initialize_import_meta_object_callback(
@@ -294,25 +1114,85 @@ pub extern "C" fn host_initialize_import_meta_object_callback(
     // 获取 ModuleLoader
     let state_ptr = scope.get_data(1);
     if state_ptr.is_null() {
-        eprintln!("错误: 在 resolve_module_callback 中的 ModuleLoader 为空 ");
+        eprintln!("错误: 在 host_initialize_import_meta_object_callback 中的 ModuleLoader 为空 ");
         return;
     }
     let module_loader = unsafe { &mut *(state_ptr as *mut ModuleLoader) };
 
-    // 模块 hash
-    let module_id: i32 = module.get_identity_hash().into();
-    // 根据模块 hash 查找文件夹
-    let dir_name = module_loader
-        .id_to_path_map
-        .get(&module_id) // 根据模块 hash 查找文件路径
-        .unwrap()
-        .parent() // 获取目录
-        .unwrap();
+    // 根据模块注册表查找文件路径, 找不到时 import.meta 保持为空对象
+    let Some(module_path) = module_loader
+        .modules
+        .get_by_module(module)
+        .map(|record| record.path.clone())
+    else {
+        return;
+    };
+    let is_main = module_loader.main_module_path.as_ref() == Some(&module_path);
+
+    // 只有文件模块才有 filename 和 dirname
+    let url = if module_path.is_absolute() {
+        set_meta_string(&mut scope, meta, "filename", &module_path.to_string_lossy());
+        if let Some(dir_name) = module_path.parent() {
+            set_meta_string(&mut scope, meta, "dirname", &dir_name.to_string_lossy());
+        }
+        path_to_file_url(&module_path)
+    } else {
+        module_path.to_string_lossy().into_owned()
+    };
+    set_meta_string(&mut scope, meta, "url", &url);
+
+    let main_key = v8::String::new(&mut scope, "main").unwrap();
+    let main_value = v8::Boolean::new(&mut scope, is_main);
+    meta.set(&mut scope, main_key.into(), main_value.into());
+
+    // import.meta.resolve, 通过 data 传入模块路径
+    let resolve_key = v8::String::new(&mut scope, "resolve").unwrap();
+    let referrer_path = v8::String::new(&mut scope, &module_path.to_string_lossy()).unwrap();
+    if let Some(resolve_fn) = v8::Function::builder(import_meta_resolve)
+        .data(referrer_path.into())
+        .build(&mut scope)
+    {
+        meta.set(&mut scope, resolve_key.into(), resolve_fn.into());
+    }
+}
+
+/// 在 import.meta 上设置字符串属性
+fn set_meta_string(
+    scope: &mut v8::HandleScope,
+    meta: v8::Local<v8::Object>,
+    key: &str,
+    value: &str,
+) {
+    let key = v8::String::new(scope, key).unwrap();
+    if let Some(value) = v8::String::new(scope, value) {
+        meta.set(scope, key.into(), value.into());
+    }
+}
+
+/// import.meta.resolve(specifier)
+///
+/// 与 import 使用相同的解析规则，只解析不加载，解析失败时抛出异常
+fn import_meta_resolve(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut return_value: v8::ReturnValue,
+) {
+    let referrer_path = args.data().to_rust_string_lossy(scope); // 模块路径
+    let specifier_str = args.get(0).to_rust_string_lossy(scope); // 与 import() 一样转换为字符串
 
-    // 在 import.meta 上设置 dirname 属性
-    let key = v8::String::new(&mut scope, "dirname").unwrap();
-    let dir_name_str = v8::String::new(&mut scope, dir_name.to_str().unwrap()).unwrap();
+    let state_ptr = scope.get_data(1); // 获取 ModuleLoader 指针
+    if state_ptr.is_null() {
+        eprintln!("错误: 在 import_meta_resolve 中的 ModuleLoader 为空 ");
+        return;
+    }
+    let module_loader = unsafe { &mut *(state_ptr as *mut ModuleLoader) };
 
-    // 设置 meta.dirname
-    meta.set(&mut scope, key.into(), dir_name_str.into());
+    match module_loader.resolve_url(&specifier_str, Path::new(&referrer_path)) {
+        Ok(url) => {
+            if let Some(url) = v8::String::new(scope, &url) {
+                return_value.set(url.into());
+            }
+        }
+        Err(error) => throw_error_with_code(scope, &error.message, error.code),
+    }
 }
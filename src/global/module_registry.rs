@@ -0,0 +1,146 @@
+use std::{
+    collections::BTreeMap, // 有序键值对映射
+    path::{Path, PathBuf}, // 路径操作
+};
+
+/// 模块在注册表中的编号，在同一次执行中唯一且不会被复用
+pub(crate) type ModuleId = usize;
+
+/// 模块的类型，决定合成模块执行时导出什么
+pub(crate) enum ModuleKind {
+    Source, // 由源代码编译的 ES 模块（文件、内存中的入口模块、由 JS 实现的内置模块）
+    Json(v8::Global<v8::Value>), // JSON 模块，值为解析后作为 default 导出的值
+    CommonJs(Vec<String>), // 被 import 的 CommonJS 模块，值为从源代码中识别出的命名导出
+    Builtin(String), // 由 Rust 实现的内置模块，值为它在注册表中的名称
+}
+
+/// 注册表中的一个模块
+pub(crate) struct ModuleRecord {
+    pub(crate) path: PathBuf, // 绝对路径（内置模块为 `node:fs`、`zjs:name` 这样的键）
+    pub(crate) kind: ModuleKind, // 模块的类型
+    pub(crate) source: Option<String>, // 模块的源代码，JSON 模块和由 Rust 实现的内置模块没有
+    pub(crate) dependencies: Vec<ModuleId>, // 静态 import 解析得到的依赖，按解析的顺序排列
+    module: v8::Global<v8::Module>, // V8 中的模块
+}
+
+impl ModuleRecord {
+    /// 获取当前作用域中的模块引用
+    pub(crate) fn module<'s>(
+        &self,
+        scope: &mut v8::HandleScope<'s, ()>,
+    ) -> v8::Local<'s, v8::Module> {
+        v8::Local::new(scope, &self.module)
+    }
+}
+
+/// 已加载模块的信息，通过 JsRuntime::module_info 查询
+#[derive(Debug, Clone)]
+pub struct ModuleInfo {
+    pub path: PathBuf, // 绝对路径（内置模块为 `node:fs`、`zjs:name` 这样的键）
+    pub status: v8::ModuleStatus, // 模块在 V8 中的状态，例如已实例化、已执行、执行失败
+    pub dependencies: Vec<PathBuf>, // 静态 import 的依赖的路径
+    pub source: Option<String>, // 源代码，JSON 模块和由 Rust 实现的内置模块没有
+}
+
+/// 模块注册表 - 为每个模块分配唯一的编号，并支持从 V8 模块和路径两个方向查询
+///
+/// V8 的 identity hash 并不保证唯一，所以它只用于缩小查找范围，
+/// 同一个 hash 下的模块再逐个比较句柄，两个模块的 hash 相同时也不会查到错误的模块
+#[derive(Default)]
+pub(crate) struct ModuleRegistry {
+    records: Vec<ModuleRecord>,            // 所有模块，下标即模块编号
+    by_hash: BTreeMap<i32, Vec<ModuleId>>, // identity hash 到具有该 hash 的模块编号
+    by_path: BTreeMap<PathBuf, ModuleId>,  // 路径到模块编号，同一个路径只对应最后注册的模块
+}
+
+impl ModuleRegistry {
+    /// 注册模块
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `module`: V8 中的模块
+    /// - `path`: 模块的绝对路径（内置模块为 `node:fs`、`zjs:name` 这样的键）
+    /// - `kind`: 模块的类型
+    /// - `source`: 模块的源代码
+    ///
+    /// # 返回
+    /// 返回分配给模块的编号
+    pub(crate) fn insert(
+        &mut self,
+        scope: &mut v8::HandleScope,
+        module: v8::Local<v8::Module>,
+        path: &Path,
+        kind: ModuleKind,
+        source: Option<String>,
+    ) -> ModuleId {
+        let id = self.records.len();
+        let hash_id: i32 = module.get_identity_hash().into();
+
+        self.records.push(ModuleRecord {
+            path: path.to_path_buf(),
+            kind,
+            source,
+            dependencies: Vec::new(),
+            module: v8::Global::new(scope, module),
+        });
+        self.by_hash.entry(hash_id).or_default().push(id);
+        self.by_path.insert(path.to_path_buf(), id);
+
+        id
+    }
+
+    /// 查询 V8 模块的编号，没有注册过的模块返回 None
+    pub(crate) fn id_of(&self, module: v8::Local<v8::Module>) -> Option<ModuleId> {
+        let hash_id: i32 = module.get_identity_hash().into();
+        self.by_hash
+            .get(&hash_id)?
+            .iter()
+            .copied()
+            .find(|&id| self.records[id].module == module)
+    }
+
+    /// 查询编号对应的记录
+    pub(crate) fn get(&self, id: ModuleId) -> Option<&ModuleRecord> {
+        self.records.get(id)
+    }
+
+    /// 查询 V8 模块对应的记录
+    pub(crate) fn get_by_module(&self, module: v8::Local<v8::Module>) -> Option<&ModuleRecord> {
+        self.id_of(module).map(|id| &self.records[id])
+    }
+
+    /// 查询路径对应的记录
+    pub(crate) fn get_by_path(&self, path: &Path) -> Option<&ModuleRecord> {
+        self.by_path.get(path).map(|&id| &self.records[id])
+    }
+
+    /// 记录 referrer 静态 import 了 dependency，重复的依赖只记录一次
+    pub(crate) fn add_dependency(&mut self, referrer: ModuleId, dependency: ModuleId) {
+        let dependencies = &mut self.records[referrer].dependencies;
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+
+    /// 查询路径对应的模块信息
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域，用于读取模块的状态
+    /// - `path`: 模块的绝对路径（内置模块为 `node:fs`、`zjs:name` 这样的键）
+    pub(crate) fn info(&self, scope: &mut v8::HandleScope<()>, path: &Path) -> Option<ModuleInfo> {
+        let record = self.get_by_path(path)?;
+        let status = record.module(scope).get_status();
+        let dependencies = record
+            .dependencies
+            .iter()
+            .map(|&id| self.records[id].path.clone())
+            .collect();
+
+        Some(ModuleInfo {
+            path: record.path.clone(),
+            status,
+            dependencies,
+            source: record.source.clone(),
+        })
+    }
+}
@@ -0,0 +1,937 @@
+use serde_json::Value;
+use std::{
+    ffi::OsString,                    // 拼接扩展名
+    fs,                               // 文件系统操作
+    path::{Component, Path, PathBuf}, // 路径操作
+};
+
+/// import 时 package.json 的 exports、imports 中支持的条件
+///
+/// 按 package.json 中的书写顺序匹配第一个支持的条件，zjs 条件可以为本运行时提供专门的入口
+pub(crate) const IMPORT_CONDITIONS: &[&str] = &["zjs", "import", "default"];
+
+/// require() 时 package.json 的 exports、imports 中支持的条件
+pub(crate) const REQUIRE_CONDITIONS: &[&str] = &["zjs", "require", "default"];
+
+/// 解析文件时依次尝试的扩展名
+const EXTENSIONS: [&str; 4] = ["js", "mjs", "cjs", "json"];
+
+/// 模块解析失败的错误，错误码与 Node.js 一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolveError {
+    pub(crate) code: &'static str, // 错误码，例如 ERR_MODULE_NOT_FOUND
+    pub(crate) message: String,    // 错误信息
+}
+
+impl ResolveError {
+    fn new(code: &'static str, message: String) -> Self {
+        Self { code, message }
+    }
+
+    /// 找不到模块或包
+    pub(crate) fn not_found(message: String) -> Self {
+        Self::new("ERR_MODULE_NOT_FOUND", message)
+    }
+
+    /// package.json 中的 exports、imports 目标不合法
+    fn invalid_target(target: &str, package_dir: &Path, referrer_path: &Path) -> Self {
+        let message = format!(
+            "Invalid \"exports\" or \"imports\" target '{}' defined in {} imported from {}",
+            target,
+            package_dir.join("package.json").display(),
+            referrer_path.display()
+        );
+        Self::new("ERR_INVALID_PACKAGE_TARGET", message)
+    }
+}
+
+/// 模块的类型，由 import 属性中的 type 决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ModuleType {
+    JavaScript, // 没有 type 属性的普通 ES 模块
+    Json,       // with { type: "json" } 导入的 JSON 模块
+}
+
+impl ModuleType {
+    /// 根据 import 属性确定模块类型
+    ///
+    /// # 参数
+    /// - `attributes`: import 属性的键值对，例如 `with { type: "json" }` 为 `[("type", "json")]`
+    ///
+    /// # 返回
+    /// 含有 type 以外的属性或不支持的 type 时返回错误
+    pub(crate) fn from_attributes(attributes: &[(String, String)]) -> Result<Self, ResolveError> {
+        let mut module_type = ModuleType::JavaScript;
+        for (key, value) in attributes {
+            module_type = match (key.as_str(), value.as_str()) {
+                ("type", "json") => ModuleType::Json,
+                ("type", _) => {
+                    let message = format!(
+                        "Import attribute \"type\" with value \"{}\" is not supported",
+                        value
+                    );
+                    return Err(ResolveError::new(
+                        "ERR_IMPORT_ATTRIBUTE_UNSUPPORTED",
+                        message,
+                    ));
+                }
+                _ => {
+                    let message = format!("Import attribute \"{}\" is not supported", key);
+                    return Err(ResolveError::new(
+                        "ERR_IMPORT_ATTRIBUTE_UNSUPPORTED",
+                        message,
+                    ));
+                }
+            };
+        }
+        Ok(module_type)
+    }
+
+    /// 检查解析得到的文件与模块类型是否一致
+    ///
+    /// .json 文件必须通过 `with { type: "json" }` 导入，JSON 模块也只能是 .json 文件
+    pub(crate) fn check_path(self, path: &Path) -> Result<(), ResolveError> {
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension == "json");
+        match (self, is_json) {
+            (ModuleType::JavaScript, true) => Err(ResolveError::new(
+                "ERR_IMPORT_ATTRIBUTE_MISSING",
+                format!(
+                    "Module \"{}\" needs an import attribute of \"type: json\"",
+                    path.display()
+                ),
+            )),
+            (ModuleType::Json, false) => Err(self.incompatible(&path.to_string_lossy())),
+            _ => Ok(()),
+        }
+    }
+
+    /// 模块与 type 属性不匹配的错误
+    pub(crate) fn incompatible(self, module: &str) -> ResolveError {
+        let message = format!("Module \"{}\" is not of type \"json\"", module);
+        ResolveError::new("ERR_IMPORT_ATTRIBUTE_TYPE_INCOMPATIBLE", message)
+    }
+}
+
+/// 解析相对路径或绝对路径（例如 ./utils、../lib/index.mjs、/abs/path）
+///
+/// # 参数
+/// - `specifier`: import 的路径
+/// - `referrer_path`: 导入者的文件路径，相对路径基于它所在的目录解析
+/// - `conditions`: 支持的条件，决定目录中 package.json 的哪些入口字段生效
+///
+/// # 返回
+/// 返回文件规范化后的绝对路径，找不到时错误信息中列出所有尝试过的候选路径
+pub(crate) fn resolve_path(
+    specifier: &str,
+    referrer_path: &Path,
+    conditions: &[&str],
+) -> Result<PathBuf, ResolveError> {
+    let referrer_dir = referrer_path.parent().unwrap_or(Path::new("")); // 导入者目录
+    let path = join_path(referrer_dir, specifier); // 解析路径
+
+    let mut candidates = Vec::new();
+    resolve_file(&path, main_fields(conditions), &mut candidates)?
+        .ok_or_else(|| not_found_in_candidates(specifier, referrer_path, &candidates))
+}
+
+/// 通过 node_modules 解析包名（例如 lodash、@scope/pkg/sub）
+///
+/// 先检查导入者所在的包能否通过自己的名称引用自己，然后从导入者所在目录开始逐级向上查找
+/// node_modules 中的包，找到后按 package.json 的 exports、module（仅 import）、main 字段解析入口文件
+///
+/// # 参数
+/// - `specifier`: import 的包名，可以带子路径
+/// - `referrer_path`: 导入者的文件路径
+/// - `conditions`: exports 中支持的条件（IMPORT_CONDITIONS 或 REQUIRE_CONDITIONS）
+///
+/// # 返回
+/// 返回入口文件规范化后的绝对路径
+pub(crate) fn resolve_package(
+    specifier: &str,
+    referrer_path: &Path,
+    conditions: &[&str],
+) -> Result<PathBuf, ResolveError> {
+    let Some((package_name, subpath)) = parse_package_specifier(specifier) else {
+        let message = format!(
+            "Invalid module specifier '{}' imported from {}",
+            specifier,
+            referrer_path.display()
+        );
+        return Err(ResolveError::new("ERR_INVALID_MODULE_SPECIFIER", message));
+    };
+    let referrer_dir = referrer_path.parent().unwrap_or(Path::new("")); // 导入者目录
+
+    // 包通过自己的名称引用自己（只在声明了 exports 时生效）
+    if let Some((package_dir, package_json)) = find_package_scope(referrer_dir)? {
+        let name = package_json.get("name").and_then(Value::as_str);
+        if let (Some(exports), Some(name)) = (package_json.get("exports"), name) {
+            if name == package_name {
+                return resolve_exports(&package_dir, &subpath, exports, referrer_path, conditions);
+            }
+        }
+    }
+
+    // 逐级向上查找 node_modules 中的包
+    for dir in referrer_dir.ancestors() {
+        if dir.ends_with("node_modules") {
+            continue; // 不查找 node_modules/node_modules
+        }
+
+        let package_dir = dir.join("node_modules").join(package_name);
+        if package_dir.is_dir() {
+            let package_json = read_package_json(&package_dir)?;
+            return resolve_package_entry(
+                &package_dir,
+                &subpath,
+                package_json.as_ref(),
+                referrer_path,
+                conditions,
+            );
+        }
+    }
+
+    Err(ResolveError::not_found(format!(
+        "Cannot find package '{}' imported from {}",
+        package_name,
+        referrer_path.display()
+    )))
+}
+
+/// 解析包内部的子路径导入（例如 #internal）
+///
+/// 从导入者所在目录向上找到所属的包，按其 package.json 的 imports 字段解析，
+/// 目标可以是包内的相对路径，也可以是其他包的名称
+///
+/// # 参数
+/// - `specifier`: 以 # 开头的导入名称
+/// - `referrer_path`: 导入者的文件路径
+/// - `conditions`: imports 中支持的条件
+pub(crate) fn resolve_package_import(
+    specifier: &str,
+    referrer_path: &Path,
+    conditions: &[&str],
+) -> Result<PathBuf, ResolveError> {
+    let referrer_dir = referrer_path.parent().unwrap_or(Path::new("")); // 导入者目录
+
+    let not_defined = |package_json: Option<&Path>| {
+        let scope = package_json
+            .map(|path| format!(" in package {}", path.display()))
+            .unwrap_or_default();
+        let message = format!(
+            "Package import specifier '{}' is not defined{} imported from {}",
+            specifier,
+            scope,
+            referrer_path.display()
+        );
+        ResolveError::new("ERR_PACKAGE_IMPORT_NOT_DEFINED", message)
+    };
+
+    if specifier == "#" || specifier.starts_with("#/") {
+        return Err(not_defined(None));
+    }
+
+    let Some((package_dir, package_json)) = find_package_scope(referrer_dir)? else {
+        return Err(not_defined(None));
+    };
+    let package_json_path = package_dir.join("package.json");
+
+    let imports = package_json.get("imports").and_then(Value::as_object);
+    let Some((target, pattern)) = imports.and_then(|imports| match_subpath(imports, specifier))
+    else {
+        return Err(not_defined(Some(&package_json_path)));
+    };
+
+    resolve_target(
+        &package_dir,
+        target,
+        pattern.as_deref(),
+        true,
+        referrer_path,
+        conditions,
+    )?
+    .ok_or_else(|| not_defined(Some(&package_json_path)))
+}
+
+/// 解析找到的包中的入口文件
+///
+/// 声明了 exports 时只能导入其中列出的子路径，否则子路径直接对应包内的文件，
+/// 包本身的入口依次尝试 module（仅 import）、main 字段和 index 文件
+fn resolve_package_entry(
+    package_dir: &Path,
+    subpath: &str,
+    package_json: Option<&Value>,
+    referrer_path: &Path,
+    conditions: &[&str],
+) -> Result<PathBuf, ResolveError> {
+    if let Some(exports) = package_json.and_then(|package_json| package_json.get("exports")) {
+        return resolve_exports(package_dir, subpath, exports, referrer_path, conditions);
+    }
+
+    // 包本身是一个目录, 按目录中 package.json 的入口字段和 index 文件解析
+    let path = join_path(package_dir, subpath);
+    let mut candidates = Vec::new();
+    resolve_file(&path, main_fields(conditions), &mut candidates)?
+        .ok_or_else(|| not_found_in_candidates(&path.to_string_lossy(), referrer_path, &candidates))
+}
+
+/// 按 package.json 的 exports 字段解析子路径
+fn resolve_exports(
+    package_dir: &Path,
+    subpath: &str,
+    exports: &Value,
+    referrer_path: &Path,
+    conditions: &[&str],
+) -> Result<PathBuf, ResolveError> {
+    // 键以 . 开头时是子路径映射, 否则整个 exports 是包本身的入口
+    let subpath_map = exports
+        .as_object()
+        .filter(|exports| exports.keys().any(|key| key.starts_with('.')));
+
+    let matched = match subpath_map {
+        Some(subpath_map) => match_subpath(subpath_map, subpath),
+        None if subpath == "." => Some((exports, None)),
+        None => None,
+    };
+
+    let resolved_path = match matched {
+        Some((target, pattern)) => resolve_target(
+            package_dir,
+            target,
+            pattern.as_deref(),
+            false,
+            referrer_path,
+            conditions,
+        )?,
+        None => None,
+    };
+
+    resolved_path.ok_or_else(|| {
+        let message = format!(
+            "Package subpath '{}' is not defined by \"exports\" in {} imported from {}",
+            subpath,
+            package_dir.join("package.json").display(),
+            referrer_path.display()
+        );
+        ResolveError::new("ERR_PACKAGE_PATH_NOT_EXPORTED", message)
+    })
+}
+
+/// 在 exports、imports 的映射中查找子路径
+///
+/// 优先精确匹配，否则匹配含有一个 * 的模式，多个模式匹配时选择前缀最长的
+///
+/// # 返回
+/// 返回匹配的目标和 * 匹配到的内容
+fn match_subpath<'a>(
+    map: &'a serde_json::Map<String, Value>,
+    subpath: &str,
+) -> Option<(&'a Value, Option<String>)> {
+    if !subpath.contains('*') {
+        if let Some(target) = map.get(subpath) {
+            return Some((target, None));
+        }
+    }
+
+    map.iter()
+        .filter_map(|(key, target)| {
+            let (prefix, suffix) = key.split_once('*')?;
+            if suffix.contains('*') {
+                return None; // 只支持一个 *
+            }
+            let matched = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            if matched.is_empty() {
+                return None;
+            }
+            Some(((prefix.len(), key.len()), target, matched))
+        })
+        .max_by_key(|(order, _, _)| *order)
+        .map(|(_, target, matched)| (target, Some(matched.to_string())))
+}
+
+/// 解析 exports、imports 中的目标
+///
+/// # 参数
+/// - `package_dir`: 目标所属包的目录
+/// - `target`: 字符串、条件对象、数组或 null
+/// - `pattern`: 子路径模式中 * 匹配到的内容
+/// - `is_import`: 是否来自 imports（只有 imports 的目标可以是其他包的名称）
+/// - `referrer_path`: 导入者的文件路径
+/// - `conditions`: 支持的条件
+///
+/// # 返回
+/// 目标为 null 或没有支持的条件时返回 None
+fn resolve_target(
+    package_dir: &Path,
+    target: &Value,
+    pattern: Option<&str>,
+    is_import: bool,
+    referrer_path: &Path,
+    conditions: &[&str],
+) -> Result<Option<PathBuf>, ResolveError> {
+    match target {
+        Value::String(target) => {
+            let target = match pattern {
+                Some(pattern) => target.replace('*', pattern),
+                None => target.clone(),
+            };
+
+            if !target.starts_with("./") {
+                // imports 的目标可以是其他包, 从当前包的位置开始解析
+                let is_package = !target.starts_with("../") && !target.starts_with('/');
+                if is_import && is_package {
+                    let package_json_path = package_dir.join("package.json");
+                    return resolve_package(&target, &package_json_path, conditions).map(Some);
+                }
+                return Err(ResolveError::invalid_target(
+                    &target,
+                    package_dir,
+                    referrer_path,
+                ));
+            }
+
+            // 目标不能跳出包所在的目录
+            let escapes = Path::new(&target)
+                .components()
+                .any(|component| matches!(component, Component::ParentDir));
+            if escapes {
+                return Err(ResolveError::invalid_target(
+                    &target,
+                    package_dir,
+                    referrer_path,
+                ));
+            }
+
+            let path = package_dir.join(&target);
+            match fs::canonicalize(&path) {
+                Ok(resolved_path) if resolved_path.is_file() => Ok(Some(resolved_path)),
+                _ => Err(ResolveError::not_found(format!(
+                    "Cannot find module '{}' imported from {}",
+                    path.display(),
+                    referrer_path.display()
+                ))),
+            }
+        }
+        // 依次尝试, 跳过不合法的目标
+        Value::Array(targets) => {
+            let mut last_error = None;
+            for target in targets {
+                match resolve_target(
+                    package_dir,
+                    target,
+                    pattern,
+                    is_import,
+                    referrer_path,
+                    conditions,
+                ) {
+                    Ok(Some(resolved_path)) => return Ok(Some(resolved_path)),
+                    Ok(None) => {}
+                    Err(error) if error.code == "ERR_INVALID_PACKAGE_TARGET" => {
+                        last_error = Some(error)
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+            last_error.map_or(Ok(None), Err)
+        }
+        // 按书写顺序匹配第一个支持的条件
+        Value::Object(targets) => {
+            for (condition, target) in targets {
+                if !conditions.contains(&condition.as_str()) {
+                    continue;
+                }
+                if let Some(resolved_path) = resolve_target(
+                    package_dir,
+                    target,
+                    pattern,
+                    is_import,
+                    referrer_path,
+                    conditions,
+                )? {
+                    return Ok(Some(resolved_path));
+                }
+            }
+            Ok(None)
+        }
+        Value::Null => Ok(None),
+        _ => Err(ResolveError::invalid_target(
+            &target.to_string(),
+            package_dir,
+            referrer_path,
+        )),
+    }
+}
+
+/// 目录中 package.json 按顺序尝试的入口字段
+///
+/// module 字段指向 ES 模块的构建，只在 import 时使用；require 和 Node.js 一样只读取 main 字段
+fn main_fields(conditions: &[&str]) -> &'static [&'static str] {
+    if conditions.contains(&"import") {
+        &["module", "main"]
+    } else {
+        &["main"]
+    }
+}
+
+/// 把路径解析为文件
+///
+/// 依次尝试原路径和添加各个扩展名后的路径；路径是目录时，先尝试目录中 package.json 的
+/// 入口字段，再尝试目录下的 index 文件
+///
+/// # 参数
+/// - `path`: 要解析的路径
+/// - `main_fields`: 目录中 package.json 按顺序尝试的入口字段（解析入口字段指向的目录时为空）
+/// - `candidates`: 记录尝试过的候选路径
+///
+/// # 返回
+/// 返回找到的文件规范化后的绝对路径
+fn resolve_file(
+    path: &Path,
+    main_fields: &[&str],
+    candidates: &mut Vec<PathBuf>,
+) -> Result<Option<PathBuf>, ResolveError> {
+    if let Some(resolved_path) = resolve_with_extensions(path, true, candidates) {
+        return Ok(Some(resolved_path));
+    }
+
+    if !path.is_dir() {
+        return Ok(None);
+    }
+
+    if !main_fields.is_empty() {
+        if let Some(package_json) = read_package_json(path)? {
+            let mains = main_fields
+                .iter()
+                .filter_map(|field| package_json.get(field).and_then(Value::as_str));
+            for main in mains {
+                if let Some(resolved_path) = resolve_file(&path.join(main), &[], candidates)? {
+                    return Ok(Some(resolved_path));
+                }
+            }
+        }
+    }
+
+    // 目录下的 index 文件必须带扩展名
+    Ok(resolve_with_extensions(
+        &path.join("index"),
+        false,
+        candidates,
+    ))
+}
+
+/// 依次尝试原路径（include_exact 为 true 时）和添加各个扩展名后的路径
+///
+/// 扩展名直接拼接在文件名后面，不替换已有的扩展名（./a.b 尝试 ./a.b.js 而不是 ./a.js）
+fn resolve_with_extensions(
+    path: &Path,
+    include_exact: bool,
+    candidates: &mut Vec<PathBuf>,
+) -> Option<PathBuf> {
+    let exact = include_exact.then(|| path.to_path_buf());
+    let with_extensions = EXTENSIONS.iter().map(|extension| {
+        let mut with_extension = OsString::from(path.as_os_str());
+        with_extension.push(".");
+        with_extension.push(extension);
+        PathBuf::from(with_extension)
+    });
+
+    for candidate in exact.into_iter().chain(with_extensions) {
+        if candidate.is_file() {
+            if let Ok(resolved_path) = fs::canonicalize(&candidate) {
+                return Some(resolved_path);
+            }
+        }
+        candidates.push(candidate);
+    }
+    None
+}
+
+/// 文件是否为 CommonJS 模块
+///
+/// .cjs 文件总是 CommonJS 模块，.js 文件在所属包的 package.json 声明了 `"type": "commonjs"` 时
+/// 是 CommonJS 模块，其他文件都是 ES 模块
+pub(crate) fn is_commonjs(path: &Path) -> Result<bool, ResolveError> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("cjs") => Ok(true),
+        Some("js") => {
+            let dir = path.parent().unwrap_or(Path::new(""));
+            let package_type = find_package_scope(dir)?.and_then(|(_, package_json)| {
+                package_json
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            });
+            Ok(package_type.as_deref() == Some("commonjs"))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// 把 file:// URL 转换为文件路径，不是 file:// URL 时原样作为路径
+///
+/// 用于 createRequire(import.meta.url)，URL 中的 %XX 转义会被还原
+pub(crate) fn file_url_to_path(url: &str) -> PathBuf {
+    let Some(path) = url.strip_prefix("file://") else {
+        return PathBuf::from(url);
+    };
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(escaped) => {
+                bytes.push(escaped);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 把绝对路径转换为 file:// URL
+///
+/// 用于 import.meta.url 和 import.meta.resolve，路径中的空格、% 和非 ASCII 字符会被转义为 %XX
+pub(crate) fn path_to_file_url(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let mut url = String::with_capacity(path.len() + 7);
+    url.push_str("file://");
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~!$&'()*+,;=:@".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
+
+/// 拼接路径并去掉其中的 . 部分，使错误信息中的候选路径更易读
+fn join_path(dir: &Path, relative: &str) -> PathBuf {
+    dir.join(relative)
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+/// 找不到模块的错误，列出导入者和尝试过的候选路径
+fn not_found_in_candidates(
+    specifier: &str,
+    referrer_path: &Path,
+    candidates: &[PathBuf],
+) -> ResolveError {
+    let mut message = format!(
+        "Cannot find module '{}' imported from {}",
+        specifier,
+        referrer_path.display()
+    );
+    if !candidates.is_empty() {
+        message.push_str("\nTried:");
+        for candidate in candidates {
+            message.push_str(&format!("\n  - {}", candidate.display()));
+        }
+    }
+    ResolveError::not_found(message)
+}
+
+/// 把包名和子路径分开，例如 `@scope/pkg/sub` 分为 `@scope/pkg` 和 `./sub`
+///
+/// # 返回
+/// 包名不合法时返回 None
+fn parse_package_specifier(specifier: &str) -> Option<(&str, String)> {
+    let mut separators = specifier.match_indices('/').map(|(index, _)| index);
+
+    // 带作用域的包名包含一个斜杠
+    let name_end = if specifier.starts_with('@') {
+        separators.nth(1)
+    } else {
+        separators.next()
+    }
+    .unwrap_or(specifier.len());
+
+    let package_name = &specifier[..name_end];
+    let is_scoped = package_name.starts_with('@');
+    if package_name.is_empty()
+        || package_name.starts_with('.')
+        || package_name.split('/').any(str::is_empty)
+        || (is_scoped && !package_name.contains('/'))
+    {
+        return None;
+    }
+
+    Some((package_name, format!(".{}", &specifier[name_end..])))
+}
+
+/// 从目录开始逐级向上查找所属的包，不跨越 node_modules
+///
+/// # 返回
+/// 返回包的目录和解析后的 package.json
+fn find_package_scope(dir: &Path) -> Result<Option<(PathBuf, Value)>, ResolveError> {
+    for dir in dir.ancestors() {
+        if dir.ends_with("node_modules") {
+            break;
+        }
+        if let Some(package_json) = read_package_json(dir)? {
+            return Ok(Some((dir.to_path_buf(), package_json)));
+        }
+    }
+    Ok(None)
+}
+
+/// 读取并解析目录中的 package.json
+///
+/// # 返回
+/// 文件不存在时返回 None，内容不是合法的 JSON 时返回错误
+fn read_package_json(dir: &Path) -> Result<Option<Value>, ResolveError> {
+    let path = dir.join("package.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+
+    serde_json::from_str(&content).map(Some).map_err(|e| {
+        let message = format!("Invalid package config {}: {}", path.display(), e);
+        ResolveError::new("ERR_INVALID_PACKAGE_CONFIG", message)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 测试用的临时目录，drop 时删除
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let dir =
+                std::env::temp_dir().join(format!("zjs-resolver-{}-{}", std::process::id(), id));
+            fs::create_dir_all(&dir).unwrap();
+            Self(fs::canonicalize(dir).unwrap())
+        }
+
+        /// 创建文件（自动创建父目录），返回它的绝对路径
+        fn file(&self, relative: &str, content: &str) -> PathBuf {
+            let path = self.0.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn parses_package_specifiers() {
+        assert_eq!(
+            parse_package_specifier("lodash"),
+            Some(("lodash", ".".to_string()))
+        );
+        assert_eq!(
+            parse_package_specifier("lodash/fp/map"),
+            Some(("lodash", "./fp/map".to_string()))
+        );
+        assert_eq!(
+            parse_package_specifier("@scope/pkg"),
+            Some(("@scope/pkg", ".".to_string()))
+        );
+        assert_eq!(
+            parse_package_specifier("@scope/pkg/sub"),
+            Some(("@scope/pkg", "./sub".to_string()))
+        );
+        assert_eq!(parse_package_specifier("@scope"), None);
+        assert_eq!(parse_package_specifier("@scope//pkg"), None);
+        assert_eq!(parse_package_specifier(".hidden"), None);
+        assert_eq!(parse_package_specifier(""), None);
+    }
+
+    #[test]
+    fn matches_exact_subpath_before_patterns() {
+        let exports = json!({
+            "./*": "./any/*.js",
+            "./features/*": "./features/*.js",
+            "./features/*.js": "./features/*.js",
+            "./features/special": "./special.js",
+        });
+        let map = exports.as_object().unwrap();
+
+        let (target, pattern) = match_subpath(map, "./features/special").unwrap();
+        assert_eq!(target, "./special.js");
+        assert_eq!(pattern, None);
+
+        // 前缀最长的模式优先, 前缀相同时键更长的优先
+        let (target, pattern) = match_subpath(map, "./features/a.js").unwrap();
+        assert_eq!(target, "./features/*.js");
+        assert_eq!(pattern.as_deref(), Some("a"));
+
+        let (target, pattern) = match_subpath(map, "./other").unwrap();
+        assert_eq!(target, "./any/*.js");
+        assert_eq!(pattern.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn does_not_match_empty_pattern() {
+        let exports = json!({ "./features/*": "./features/*.js" });
+        assert!(match_subpath(exports.as_object().unwrap(), "./features/").is_none());
+        assert!(match_subpath(exports.as_object().unwrap(), "./missing").is_none());
+    }
+
+    #[test]
+    fn finds_packages_in_ancestor_node_modules() {
+        let dir = TempDir::new();
+        let referrer = dir.file("src/nested/main.js", "");
+        dir.file(
+            "node_modules/pkg/package.json",
+            r#"{ "exports": { ".": { "zjs": "./zjs.js", "default": "./index.js" }, "./feature": "./feature.js" } }"#,
+        );
+        let entry = dir.file("node_modules/pkg/zjs.js", "");
+        let feature = dir.file("node_modules/pkg/feature.js", "");
+        dir.file("node_modules/pkg/hidden.js", "");
+
+        assert_eq!(
+            resolve_package("pkg", &referrer, IMPORT_CONDITIONS),
+            Ok(entry)
+        );
+        assert_eq!(
+            resolve_package("pkg/feature", &referrer, IMPORT_CONDITIONS),
+            Ok(feature)
+        );
+
+        // 声明了 exports 时不能导入其中没有列出的文件
+        let error = resolve_package("pkg/hidden.js", &referrer, IMPORT_CONDITIONS).unwrap_err();
+        assert_eq!(error.code, "ERR_PACKAGE_PATH_NOT_EXPORTED");
+        let error = resolve_package("missing", &referrer, IMPORT_CONDITIONS).unwrap_err();
+        assert_eq!(error.code, "ERR_MODULE_NOT_FOUND");
+    }
+
+    #[test]
+    fn resolves_subpath_imports_within_the_package() {
+        let dir = TempDir::new();
+        dir.file(
+            "package.json",
+            r##"{ "imports": { "#internal/*": "./src/internal/*.js" } }"##,
+        );
+        let referrer = dir.file("src/main.js", "");
+        let internal = dir.file("src/internal/db.js", "");
+
+        assert_eq!(
+            resolve_package_import("#internal/db", &referrer, IMPORT_CONDITIONS),
+            Ok(internal)
+        );
+        let error = resolve_package_import("#other", &referrer, IMPORT_CONDITIONS).unwrap_err();
+        assert_eq!(error.code, "ERR_PACKAGE_IMPORT_NOT_DEFINED");
+    }
+
+    #[test]
+    fn appends_extensions_without_replacing_existing_ones() {
+        let dir = TempDir::new();
+        let file = dir.file("a.b.js", "");
+        dir.file("a.js", "");
+
+        let mut candidates = Vec::new();
+        let resolved = resolve_with_extensions(&dir.0.join("a.b"), true, &mut candidates);
+        assert_eq!(resolved, Some(file));
+        assert_eq!(candidates, [dir.0.join("a.b")]);
+    }
+
+    #[test]
+    fn lists_candidates_when_not_found() {
+        let dir = TempDir::new();
+        let referrer = dir.file("main.js", "");
+
+        let error = resolve_path("./missing", &referrer, IMPORT_CONDITIONS).unwrap_err();
+        assert_eq!(error.code, "ERR_MODULE_NOT_FOUND");
+        assert!(error.message.contains("missing.mjs"));
+        assert!(error.message.contains("missing.json"));
+    }
+
+    #[test]
+    fn resolves_directories_through_package_json_and_index_files() {
+        let dir = TempDir::new();
+        let referrer = dir.file("main.js", "");
+        let index = dir.file("lib/index.mjs", "");
+        dir.file("app/package.json", r#"{ "main": "./entry" }"#);
+        let entry = dir.file("app/entry.cjs", "");
+
+        assert_eq!(
+            resolve_path("./lib", &referrer, IMPORT_CONDITIONS),
+            Ok(index)
+        );
+        assert_eq!(
+            resolve_path("./app", &referrer, IMPORT_CONDITIONS),
+            Ok(entry)
+        );
+    }
+
+    fn attributes(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn determines_module_type_from_attributes() {
+        assert_eq!(ModuleType::from_attributes(&[]), Ok(ModuleType::JavaScript));
+        assert_eq!(
+            ModuleType::from_attributes(&attributes(&[("type", "json")])),
+            Ok(ModuleType::Json)
+        );
+
+        let error = ModuleType::from_attributes(&attributes(&[("type", "css")])).unwrap_err();
+        assert_eq!(error.code, "ERR_IMPORT_ATTRIBUTE_UNSUPPORTED");
+        let error = ModuleType::from_attributes(&attributes(&[("mode", "x")])).unwrap_err();
+        assert_eq!(error.code, "ERR_IMPORT_ATTRIBUTE_UNSUPPORTED");
+    }
+
+    #[test]
+    fn resolves_module_field_only_for_import() {
+        let dir = TempDir::new();
+        let referrer = dir.file("main.js", "");
+        dir.file(
+            "node_modules/pkg/package.json",
+            r#"{ "module": "./esm.js", "main": "./cjs.js" }"#,
+        );
+        let esm = dir.file("node_modules/pkg/esm.js", "");
+        let cjs = dir.file("node_modules/pkg/cjs.js", "");
+
+        assert_eq!(
+            resolve_package("pkg", &referrer, IMPORT_CONDITIONS),
+            Ok(esm)
+        );
+        assert_eq!(
+            resolve_package("pkg", &referrer, REQUIRE_CONDITIONS),
+            Ok(cjs)
+        );
+    }
+
+    #[test]
+    fn round_trips_file_urls() {
+        let path = Path::new("/tmp/my dir/100%/数据.js");
+        let url = path_to_file_url(path);
+        assert_eq!(url, "file:///tmp/my%20dir/100%25/%E6%95%B0%E6%8D%AE.js");
+        assert_eq!(file_url_to_path(&url), path);
+
+        // 不是 file:// URL 时原样作为路径
+        assert_eq!(
+            file_url_to_path("/plain/path.js"),
+            Path::new("/plain/path.js")
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::ffi::c_void;
+
+/// 近堆上限回调的状态，由 JsRuntime 持有，指针作为回调的 data 传给 V8
+pub(crate) struct HeapLimitState {
+    isolate_handle: v8::IsolateHandle, // 用于终止执行的线程安全句柄
+    extension: Option<usize>,          // 终止后临时增加的堆空间（字节）
+    extended: bool,                    // 本次执行是否已经增加过堆空间（只增加一次）
+    exceeded: bool,                    // 是否因为接近堆上限而终止了执行
+    initial_heap_limit: usize,         // V8 传给回调的初始堆上限，用于在终止后恢复
+}
+
+impl HeapLimitState {
+    /// 创建近堆上限回调的状态
+    ///
+    /// # 参数
+    /// - `isolate_handle`: V8 隔离区的线程安全句柄
+    /// - `extension`: 终止执行后临时增加的堆空间
+    pub(crate) fn new(isolate_handle: v8::IsolateHandle, extension: Option<usize>) -> Self {
+        Self {
+            isolate_handle,
+            extension,
+            extended: false,
+            exceeded: false,
+            initial_heap_limit: 0,
+        }
+    }
+
+    /// 取出并清除"已超出堆上限"的标记
+    pub(crate) fn take_exceeded(&mut self) -> bool {
+        std::mem::take(&mut self.exceeded)
+    }
+
+    /// 清除"已增加堆空间"的标记，让下一次执行在接近堆上限时可以再次增加
+    ///
+    /// # 返回
+    /// 增加过堆空间时返回需要恢复的初始堆上限，否则返回 None
+    pub(crate) fn reset_extension(&mut self) -> Option<usize> {
+        std::mem::take(&mut self.extended).then_some(self.initial_heap_limit)
+    }
+}
+
+/// 近堆上限回调函数
+///
+/// V8 的堆即将耗尽时调用，这里终止正在执行的脚本，并返回新的堆上限。
+/// 终止执行后 V8 仍需要一些内存来展开调用栈，所以第一次调用时临时增加一次堆空间，
+/// 否则 V8 会直接以 OOM 崩溃整个进程
+pub(crate) extern "C" fn near_heap_limit_callback(
+    data: *mut c_void,         // HeapLimitState 指针
+    current_heap_limit: usize, // 当前的堆上限
+    initial_heap_limit: usize, // 初始的堆上限
+) -> usize {
+    let state = unsafe { &mut *(data as *mut HeapLimitState) };
+    state.initial_heap_limit = initial_heap_limit;
+
+    state.exceeded = true;
+    state.isolate_handle.terminate_execution(); // 终止执行, execute 会返回 JsError::OutOfMemory
+
+    if state.extended {
+        return current_heap_limit;
+    }
+
+    state.extended = true;
+    current_heap_limit + state.extension.unwrap_or(current_heap_limit)
+}
@@ -13,3 +13,42 @@ macro_rules! v8_string {
         v8::String::new($scope, $value).unwrap() // 创建 V8 字符串并 unwrap（假定成功）
     };
 }
+
+/// 在 JS 端抛出一个 Error 异常
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `message`: 错误信息
+pub(crate) fn throw_error(scope: &mut v8::HandleScope, message: &str) {
+    let message = v8::String::new(scope, message).unwrap();
+    let error = v8::Exception::error(scope, message);
+    scope.throw_exception(error);
+}
+
+/// 在 JS 端抛出一个 TypeError 异常
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `message`: 错误信息
+pub(crate) fn throw_type_error(scope: &mut v8::HandleScope, message: &str) {
+    let message = v8::String::new(scope, message).unwrap();
+    let error = v8::Exception::type_error(scope, message);
+    scope.throw_exception(error);
+}
+
+/// 在 JS 端抛出一个带有 code 属性的 Error 异常（例如 ERR_MODULE_NOT_FOUND）
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `message`: 错误信息
+/// - `code`: 错误码
+pub(crate) fn throw_error_with_code(scope: &mut v8::HandleScope, message: &str, code: &str) {
+    let message = v8::String::new(scope, message).unwrap();
+    let error = v8::Exception::error(scope, message);
+    let key = v8::String::new(scope, "code").unwrap();
+    let code = v8::String::new(scope, code).unwrap();
+    error
+        .cast::<v8::Object>()
+        .set(scope, key.into(), code.into());
+    scope.throw_exception(error);
+}
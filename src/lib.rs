@@ -1,63 +1,440 @@
 mod builtin;
+mod error;
 mod global;
+mod heap_limit;
 mod helper;
+pub mod op;
+mod options;
+pub mod serde_v8;
+mod termination;
+mod unhandled_rejection;
+mod value;
 
-use builtin::async_task::{AsyncTaskDispatcher, TokioAsyncTaskManager};
+use builtin::async_task::AsyncTaskDispatcher;
+pub use builtin::async_task::{AsyncTaskValue, EventLoopHandle, TokioAsyncTaskManager};
+use builtin::registry::runtime_builtins;
+pub use builtin::registry::{BuiltinModule, ExportBuilder};
+pub use error::{JsError, JsException};
 use global::inject_global_values;
-use global::module_loader::{host_initialize_import_meta_object_callback, ModuleLoader};
-use v8::{self, ContextOptions, Local, OwnedIsolate, Value};
+use global::module_loader::{
+    host_import_module_dynamically_callback, host_initialize_import_meta_object_callback,
+    resolve_module_callback, ModuleLoader,
+};
+pub use global::module_registry::ModuleInfo;
+use heap_limit::{near_heap_limit_callback, HeapLimitState};
+pub use op::{OpError, OpFunction};
+pub use options::{Builtin, Entrypoint, ExecuteOptions, GlobalApi, RuntimeOptions};
+use serde::Serialize;
+use std::{path::Path, sync::Once, time::Duration};
+pub use termination::TerminationHandle;
+use termination::Watchdog;
+pub use unhandled_rejection::UnhandledRejectionPolicy;
+use unhandled_rejection::{promise_reject_callback, RejectionTracker};
+pub use v8;
+use v8::{self, ContextOptions, OwnedIsolate};
+pub use value::JsValue;
 
-pub struct JsRuntime<D: AsyncTaskDispatcher = TokioAsyncTaskManager> {
-    // V8 隔离区（独立的独立的堆内存 JS 执行环境）管理 JavaScript 对象的生命周期、堆内存管理、垃圾回收器、全局对象和上下文
-    isolate: v8::OwnedIsolate,
-    // 异步任务调度器
-    task_dispatcher: D,
-}
+/// 初始化 V8 平台和引擎
+///
+/// V8 平台在整个进程中只能初始化一次，重复初始化会导致崩溃，所以用 Once 保护，
+/// 之后在任意线程中创建的 JsRuntime 都共享这个平台。
+/// V8 参数只能在初始化之前设置（之后再设置会使 V8 中止进程），所以只有第一次调用的参数生效
+///
+/// # 参数
+/// - `v8_flags`: V8 命令行参数
+///
+/// # 返回
+/// 返回参数是否被应用，平台已经初始化时返回 false
+fn init_v8_platform(v8_flags: &[String]) -> bool {
+    static V8_INIT: Once = Once::new();
 
-impl<D: AsyncTaskDispatcher> Default for JsRuntime<D> {
-    // 初始化 V8 引擎
-    fn default() -> Self {
+    let mut initialized = false; // 本次调用是否初始化了平台
+    V8_INIT.call_once(|| {
+        if !v8_flags.is_empty() {
+            v8::V8::set_flags_from_string(&v8_flags.join(" "));
+        }
         // 创建 V8 平台，参数 0 表示线程数，false 表示不启用调试
         let platform = v8::new_default_platform(0, false).make_shared();
         // 初始化 V8 平台
         v8::V8::initialize_platform(platform);
         // 初始化 V8 引擎
         v8::V8::initialize();
+        initialized = true;
+    });
+    initialized
+}
+
+/// JS 运行时
+///
+/// 每个 JsRuntime 拥有独立的 V8 隔离区，可以在同一进程中依次或在多个线程中同时创建多个实例。
+/// 隔离区不能跨线程移动，同一线程中的多个实例需要按创建的相反顺序销毁
+pub struct JsRuntime {
+    // V8 隔离区（独立的独立的堆内存 JS 执行环境）管理 JavaScript 对象的生命周期、堆内存管理、垃圾回收器、全局对象和上下文
+    isolate: v8::OwnedIsolate,
+    // 异步任务调度器
+    task_dispatcher: TokioAsyncTaskManager,
+    // 未处理的 Promise rejection 跟踪器（Box 保证地址稳定, 指针存放在 isolate 中）
+    rejection_tracker: Box<RejectionTracker>,
+    // 可以 import 的内置模块（启用的自带模块和嵌入方注册的模块）
+    builtins: Vec<BuiltinModule>,
+    // 注入到全局对象的 API
+    globals: Vec<GlobalApi>,
+    // 注入到全局对象的 op 函数
+    ops: Vec<OpFunction>,
+    // 近堆上限回调的状态（Box 保证地址稳定, 指针作为回调的 data 传给 V8）
+    heap_limit_state: Box<HeapLimitState>,
+    // 线程安全的终止句柄
+    termination_handle: TerminationHandle,
+    // 每次 execute 的执行时间上限
+    execution_timeout: Option<Duration>,
+    // 最近一次执行所在的上下文，call_export 在其中调用导出的函数
+    context: Option<v8::Global<v8::Context>>,
+    // 最近一次执行的入口模块导出的命名空间
+    module_namespace: Option<v8::Global<v8::Object>>,
+    // 最近一次执行的模块加载器（Box 保证地址稳定, 指针存放在 isolate 中）
+    module_loader: Option<Box<ModuleLoader>>,
+}
+
+impl Default for JsRuntime {
+    // 使用默认选项初始化 V8 引擎
+    fn default() -> Self {
+        Self::from_options(RuntimeOptions::default())
+    }
+}
+
+impl JsRuntime {
+    /// 根据运行时选项创建 JsRuntime 实例
+    fn from_options(options: RuntimeOptions) -> Self {
+        // 进程中第一个 JsRuntime 负责设置 V8 参数并初始化 V8 平台
+        if !init_v8_platform(&options.v8_flags) && !options.v8_flags.is_empty() {
+            eprintln!("警告: V8 已经初始化, 忽略 V8 参数 {:?}", options.v8_flags);
+        }
+
+        // 设置堆大小限制
+        let mut create_params = v8::CreateParams::default();
+        if let Some(max_heap_size) = options.max_heap_size {
+            create_params = create_params.heap_limits(options.initial_heap_size, max_heap_size);
+        }
 
         // 创建 V8 隔离区（隔离的 JS 执行环境）
-        let isolate = v8::Isolate::new(Default::default());
+        let mut isolate = v8::Isolate::new(create_params);
+
+        // 注册近堆上限回调, 堆即将耗尽时终止脚本而不是让整个进程崩溃
+        let mut heap_limit_state = Box::new(HeapLimitState::new(
+            isolate.thread_safe_handle(),
+            options.heap_limit_extension,
+        ));
+        let heap_limit_state_ptr = &mut *heap_limit_state as *mut HeapLimitState;
+        isolate
+            .add_near_heap_limit_callback(near_heap_limit_callback, heap_limit_state_ptr as *mut _);
+
+        let termination_handle = TerminationHandle::new(isolate.thread_safe_handle());
+
+        // 启用的自带内置模块, 之后是嵌入方注册的模块（同名时覆盖自带模块）
+        let builtins = runtime_builtins(&options.builtin_modules)
+            .into_iter()
+            .chain(options.extensions)
+            .collect();
 
         Self {
             isolate,
-            // 创建默认的异步任务管理器
-            task_dispatcher: D::default(),
+            task_dispatcher: options.task_dispatcher,
+            rejection_tracker: Box::new(RejectionTracker::new(options.unhandled_rejection_policy)),
+            builtins,
+            globals: options.globals,
+            ops: options.ops,
+            heap_limit_state,
+            termination_handle,
+            execution_timeout: options.execution_timeout,
+            context: None,
+            module_namespace: None,
+            module_loader: None,
         }
     }
 }
 
+impl Drop for JsRuntime {
+    // Global 句柄要在隔离区销毁之前释放
+    fn drop(&mut self) {
+        self.task_dispatcher.cancel_all_tasks(&mut self.isolate);
+        self.drop_module_loader();
+        self.module_namespace.take();
+        self.context.take();
+    }
+}
+
 impl JsRuntime {
     /// 创建新的 JsRuntime 实例
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// 异步执行 JS 脚本
+    /// 根据运行时选项创建 JsRuntime 实例
+    ///
+    /// # 参数
+    /// - `options`: 运行时选项（堆大小、V8 参数、异步任务调度器、注入的内置模块和全局 API）
+    pub fn with_options(options: RuntimeOptions) -> Self {
+        Self::from_options(options)
+    }
+
+    /// 获取异步任务调度器，可用于为长生命周期的句柄 ref/unref 事件循环
+    pub fn task_dispatcher(&self) -> &TokioAsyncTaskManager {
+        &self.task_dispatcher
+    }
+
+    /// 获取线程安全的终止句柄，可以在其他线程中终止正在运行的 execute
+    pub fn termination_handle(&self) -> TerminationHandle {
+        self.termination_handle.clone()
+    }
+
+    /// 设置未处理的 Promise rejection 的处理策略（默认打印到 stderr）
+    pub fn set_unhandled_rejection_policy(&mut self, policy: UnhandledRejectionPolicy) {
+        self.rejection_tracker.policy = policy;
+    }
+
+    /// 异步执行 JS 脚本，并调用导出的 main() 函数
+    ///
+    /// # 参数
+    /// - `entry_script_path`: JS 脚本文件路径
+    ///
+    /// # 返回
+    /// 返回 main() 函数的执行结果，加载、编译、实例化或执行失败时返回 JsError
+    pub async fn execute(&mut self, entry_script_path: &str) -> Result<JsValue, JsError> {
+        self.execute_with_options(entry_script_path, ExecuteOptions::default())
+            .await
+    }
+
+    /// 异步执行 JS 脚本，按选项调用入口函数
     ///
     /// # 参数
     /// - `entry_script_path`: JS 脚本文件路径
+    /// - `options`: 执行选项（入口函数名称、参数）
     ///
     /// # 返回
-    /// 返回 main() 函数的执行结果
-    pub async fn execute(&mut self, entry_script_path: &str) -> Local<'_, Value> {
+    /// 返回入口函数的执行结果，结果是 Promise 时返回它完成后的值，不调用入口时返回 undefined
+    pub async fn execute_with_options(
+        &mut self,
+        entry_script_path: &str,
+        options: ExecuteOptions,
+    ) -> Result<JsValue, JsError> {
+        self.run(EntrySource::File(entry_script_path), options)
+            .await
+    }
+
+    /// 执行内存中的 ES 模块源代码，只执行顶层代码，不调用任何导出
+    ///
+    /// # 参数
+    /// - `name`: 模块的虚拟路径（相对路径基于当前工作目录），模块中的相对 import 基于它所在的目录解析
+    /// - `source`: 模块的源代码
+    pub async fn eval_module(&mut self, name: &str, source: &str) -> Result<JsValue, JsError> {
+        self.eval_module_with_options(name, source, ExecuteOptions::new().no_entrypoint())
+            .await
+    }
+
+    /// 执行内存中的 ES 模块源代码，按选项调用入口函数
+    ///
+    /// # 参数
+    /// - `name`: 模块的虚拟路径（相对路径基于当前工作目录），模块中的相对 import 基于它所在的目录解析
+    /// - `source`: 模块的源代码
+    /// - `options`: 执行选项（入口函数名称、参数）
+    pub async fn eval_module_with_options(
+        &mut self,
+        name: &str,
+        source: &str,
+        options: ExecuteOptions,
+    ) -> Result<JsValue, JsError> {
+        self.run(EntrySource::Module { name, source }, options)
+            .await
+    }
+
+    /// 执行内存中的经典脚本（非 ES 模块）源代码
+    ///
+    /// 脚本中不能使用静态 import，动态 import() 基于当前工作目录解析
+    ///
+    /// # 返回
+    /// 返回脚本最后一个表达式的值，值是 Promise 时返回它完成后的值
+    pub async fn eval_script(&mut self, source: &str) -> Result<JsValue, JsError> {
+        self.run(EntrySource::Script(source), ExecuteOptions::default())
+            .await
+    }
+
+    /// 调用最近一次执行的入口模块导出的函数
+    ///
+    /// 函数在模块原来的上下文中执行，模块的状态在多次调用之间保留，
+    /// 可以只加载一次脚本，之后多次调用它导出的处理函数
+    ///
+    /// # 参数
+    /// - `name`: 导出的函数名称
+    /// - `args`: 传给函数的参数
+    ///
+    /// # 返回
+    /// 返回函数的执行结果，结果是 Promise 时运行事件循环并返回它完成后的值
+    pub async fn call_export<T: Serialize>(
+        &mut self,
+        name: &str,
+        args: impl IntoIterator<Item = T>,
+    ) -> Result<JsValue, JsError> {
+        self.run(EntrySource::Export(name), ExecuteOptions::new().args(args))
+            .await
+    }
+
+    /// 读取最近一次执行的入口模块导出的值
+    ///
+    /// # 参数
+    /// - `name`: 导出的名称
+    pub fn get_export(&mut self, name: &str) -> Result<JsValue, JsError> {
+        let (Some(context), Some(module_namespace)) = (&self.context, &self.module_namespace)
+        else {
+            return Err(JsError::NotLoaded);
+        };
+
+        let scope = &mut v8::HandleScope::new(&mut self.isolate);
+        let context = v8::Local::new(scope, context);
+        let scope = &mut v8::ContextScope::new(scope, context);
+        let module_namespace = v8::Local::new(scope, module_namespace);
+
+        let key = v8::String::new(scope, name).unwrap();
+        if !module_namespace.has(scope, key.into()).unwrap_or(false) {
+            return Err(JsError::MissingExport(name.to_string()));
+        }
+
+        let value = module_namespace
+            .get(scope, key.into())
+            .unwrap_or_else(|| v8::undefined(scope).into());
+        JsValue::from_v8(scope, value).map_err(JsError::Conversion)
+    }
+
+    /// 查询最近一次执行加载的模块的信息（状态、依赖和源代码）
+    ///
+    /// # 参数
+    /// - `path`: 模块规范化后的绝对路径（内置模块为 `node:fs`、`zjs:name` 这样的键）
+    ///
+    /// # 返回
+    /// 还没有执行过或者没有加载该模块时返回 None
+    pub fn module_info(&mut self, path: impl AsRef<Path>) -> Option<ModuleInfo> {
+        let module_loader = self.module_loader.as_deref()?;
+
+        let scope = &mut v8::HandleScope::new(&mut self.isolate);
+        module_loader.module_info(scope, path.as_ref())
+    }
+
+    /// 执行入口，并把执行期间的终止（超时、终止句柄、堆耗尽）转换为对应的错误
+    async fn run(
+        &mut self,
+        entry: EntrySource<'_>,
+        mut options: ExecuteOptions,
+    ) -> Result<JsValue, JsError> {
+        // 参数无法序列化时不执行任何代码
+        if let Some(error) = options.arg_error.take() {
+            return Err(JsError::Conversion(error));
+        }
+
+        let termination_handle = self.termination_handle.clone();
+        termination_handle.reset(); // 之前的终止请求不影响这次执行
+        self.rejection_tracker.clear(); // 之前的执行留下的 rejection 也不影响这次执行
+
+        // 设置了执行时间上限时启动看门狗, 超时后终止执行
+        let watchdog = self
+            .execution_timeout
+            .map(|timeout| Watchdog::start(termination_handle.clone(), timeout));
+
+        // 脚本在等待异步任务时被终止, 不再等待事件循环
+        let result = tokio::select! {
+            result = self.run_entry(entry, options) => result,
+            _ = termination_handle.terminated() => Err(JsError::Terminated),
+        };
+        drop(watchdog); // 停止看门狗, drop 返回之后看门狗不会再终止执行
+
+        // 取出这次执行中的终止状态, 并取消终止让隔离区可以被清理或继续使用
+        let out_of_memory = self.heap_limit_state.take_exceeded();
+        let terminated = termination_handle.take_terminated();
+        if out_of_memory || terminated {
+            self.isolate.cancel_terminate_execution();
+        }
+
+        // 被终止的执行留下的任务不再等待, 避免阻塞之后的执行
+        if result.is_err() && (out_of_memory || terminated) {
+            self.task_dispatcher.cancel_all_tasks(&mut self.isolate);
+        }
+
+        let result = match result {
+            // 执行已经成功完成, 之后才到达的终止请求不影响结果
+            Ok(value) => Ok(value),
+            // 堆即将耗尽时脚本被终止, 无论脚本停在哪一步都报告为内存不足
+            Err(_) if out_of_memory => {
+                // 堆已经接近上限, 不再保留可能占用大量内存的上下文, 之后的 call_export 返回 JsError::NotLoaded
+                self.drop_module_loader();
+                self.module_namespace = None;
+                self.context = None;
+                self.isolate.low_memory_notification();
+                Err(JsError::OutOfMemory)
+            }
+            // 被终止句柄或看门狗终止
+            Err(_) if terminated => Err(JsError::Terminated),
+            Err(error) => Err(error),
+        };
+
+        if out_of_memory {
+            self.restore_heap_limit();
+        }
+        result
+    }
+
+    /// 把接近堆上限时临时增加的堆上限恢复为初始值，
+    /// 这样之后的执行再次接近堆上限时仍然可以临时增加堆空间来安全地终止
+    fn restore_heap_limit(&mut self) {
+        if let Some(initial_heap_limit) = self.heap_limit_state.reset_extension() {
+            // 移除回调时 V8 把堆上限恢复为给定的值, 然后重新注册回调
+            self.isolate
+                .remove_near_heap_limit_callback(near_heap_limit_callback, initial_heap_limit);
+            let heap_limit_state_ptr = &mut *self.heap_limit_state as *mut HeapLimitState;
+            self.isolate.add_near_heap_limit_callback(
+                near_heap_limit_callback,
+                heap_limit_state_ptr as *mut _,
+            );
+        }
+    }
+
+    /// 释放模块加载器，并清除隔离区中指向它的指针
+    fn drop_module_loader(&mut self) {
+        self.isolate.set_data(1, std::ptr::null_mut());
+        self.module_loader = None;
+    }
+
+    /// 创建执行上下文并执行入口，运行事件循环直到所有异步任务完成
+    async fn run_entry(
+        &mut self,
+        entry: EntrySource<'_>,
+        options: ExecuteOptions,
+    ) -> Result<JsValue, JsError> {
+        // 调用导出的函数时沿用之前的上下文
+        if let EntrySource::Export(name) = entry {
+            return self.run_export(name, options.args).await;
+        }
+
+        // 新的执行使用新的上下文和模块加载器, 之前的模块不再可以访问, 它们留下的任务也被取消
+        self.task_dispatcher.cancel_all_tasks(&mut self.isolate);
+        self.drop_module_loader();
+        self.module_namespace = None;
+        self.context = None;
+
         let isolate_ptr = &mut self.isolate as *mut OwnedIsolate; // 获取 isolate 的可变指针（用于 unsafe 操作）
         let scope = &mut v8::HandleScope::new(unsafe { &mut *isolate_ptr }); // 在这个作用域内创建的所有 JavaScript 值都会被追踪, 当 scope 离开作用域时，自动清理未被引用的对象（临时的"工作台"，管理当前正在使用的 JavaScript 值的句柄）
 
         let task_dispatcher_ptr = &self.task_dispatcher as *const _ as *mut _;
         self.isolate.set_data(0, task_dispatcher_ptr); // 在 isolate 中存储异步任务管理器的指针, 以便后续 run_event_loop 时使用
-        let module_loader = ModuleLoader::init_and_inject(&mut self.isolate); // 在隔离上下文中注入 module_loader 来管理路径、模块、文件之间的关联
+
+        // 在隔离区中存储 module_loader 的指针, 由它在模块回调中管理路径、模块、文件之间的关联
+        let mut module_loader = Box::new(ModuleLoader::new(self.builtins.clone()));
+        let module_loader_ptr = &mut *module_loader as *mut ModuleLoader;
+        self.isolate.set_data(1, module_loader_ptr as *mut _);
+        self.module_loader = Some(module_loader);
+        let module_loader = unsafe { &mut *module_loader_ptr };
+        let rejection_tracker_ptr = &mut *self.rejection_tracker as *mut RejectionTracker;
+        self.isolate.set_data(2, rejection_tracker_ptr as *mut _); // 在 isolate 中存储 rejection 跟踪器的指针, 以便 promise_reject_callback 时使用
 
         let global_api_template = v8::ObjectTemplate::new(scope); // 创建对象模板, v8::ObjectTemplate 允许你在 Rust 中预定义 JavaScript 对象的结构，包括属性、方法和访问器，然后基于这个模板快速创建多个相似的对象。
-        inject_global_values(scope, &global_api_template); // 注入 Global API, 目前有 print 函数
+        inject_global_values(scope, &global_api_template, &self.globals, &self.ops); // 注入 Global API（print 函数和 op 函数）
 
         // 创建 V8 执行上下文, 注入 Global API 方法
         let context = v8::Context::new(
@@ -68,74 +445,225 @@ impl JsRuntime {
             },
         );
 
-        // TODO: 设置动态 import() 的处理函数
-        self.isolate.set_host_import_module_dynamically_callback(
-            host_import_module_dynamically_callback_example,
-        );
+        // 设置动态 import() 的处理函数
+        self.isolate
+            .set_host_import_module_dynamically_callback(host_import_module_dynamically_callback);
 
-        // 设置 import.meta 初始化函数, 为 import.meta.dirname 设置值
+        // 设置 Promise reject 回调, 跟踪未处理的 rejection
+        self.isolate
+            .set_promise_reject_callback(promise_reject_callback);
+
+        // 设置 import.meta 初始化函数, 为 import.meta 设置 url、dirname 等值
         self.isolate
             .set_host_initialize_import_meta_object_callback(
                 host_initialize_import_meta_object_callback,
             );
 
+        self.context = Some(v8::Global::new(scope, context));
         let scope = &mut v8::ContextScope::new(scope, context); // 在新上下文中创建作用域
 
-        // 加载并编译 main 模块
-        let module = module_loader
-            .create_first_module(scope, entry_script_path)
-            .unwrap();
+        let result = match entry {
+            // 经典脚本的结果是最后一个表达式的值
+            EntrySource::Script(source) => {
+                let script = module_loader.create_inline_script(scope, source)?;
+
+                let tc_scope = &mut v8::TryCatch::new(scope); // 捕获脚本执行时抛出的异常
+                match script.run(tc_scope) {
+                    Some(result) => result,
+                    None => return Err(JsError::Evaluate(JsException::from_try_catch(tc_scope))),
+                }
+            }
+            // 模块的结果是入口函数的返回值
+            EntrySource::File(path) => {
+                let module = module_loader.create_first_module(scope, path)?; // 加载并编译 main 模块
+                self.evaluate_module(scope, module, options).await?
+            }
+            EntrySource::Module { name, source } => {
+                let module = module_loader.create_inline_module(scope, name, source)?;
+                self.evaluate_module(scope, module, options).await?
+            }
+            EntrySource::Export(_) => unreachable!(),
+        };
+
+        self.settle_result(scope, result).await
+    }
+
+    /// 在之前的上下文中调用入口模块导出的函数
+    async fn run_export(&mut self, name: &str, args: Vec<JsValue>) -> Result<JsValue, JsError> {
+        let isolate_ptr = &mut self.isolate as *mut OwnedIsolate;
+        let scope = &mut v8::HandleScope::new(unsafe { &mut *isolate_ptr });
 
-        // 执行模块（顶级代码），主要用于: 执行模块的顶层代码（变量声明、初始化等）、处理模块的导入/导出、但不会自动调用导出的函数
-        module.evaluate(scope).unwrap();
+        let (Some(context), Some(module_namespace)) = (&self.context, &self.module_namespace)
+        else {
+            return Err(JsError::NotLoaded);
+        };
+        let context = v8::Local::new(scope, context);
+        let module_namespace = v8::Local::new(scope, module_namespace);
 
-        let module_namespace = module.get_module_namespace(); // 获取 js 模块导出的命名空间
-        let main_fn_name = v8::String::new(scope, "main").unwrap();
+        // JsRuntime 可能在两次调用之间被移动过, 重新存储异步任务管理器和 rejection 跟踪器的指针
+        let task_dispatcher_ptr = &self.task_dispatcher as *const _ as *mut _;
+        self.isolate.set_data(0, task_dispatcher_ptr);
+        let rejection_tracker_ptr = &mut *self.rejection_tracker as *mut RejectionTracker;
+        self.isolate.set_data(2, rejection_tracker_ptr as *mut _);
 
-        // 获取 main 函数
-        let main_fn = module_namespace
-            .to_object(scope)
-            .unwrap()
-            .get(scope, main_fn_name.into())
-            .unwrap();
+        let scope = &mut v8::ContextScope::new(scope, context);
+        let result = call_exported_function(scope, module_namespace, name, args)?;
+
+        self.settle_result(scope, result).await
+    }
 
-        // 检查是否确实是函数
-        if !main_fn.is_function() {
-            panic!("main 函数不存在");
+    /// 运行事件循环直到所有异步任务完成，结果是 Promise 时取出它完成后的值
+    async fn settle_result<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        result: v8::Local<'s, v8::Value>,
+    ) -> Result<JsValue, JsError> {
+        // 入口函数返回的 Promise 失败同样会转换为 JsError;
+        // 入口函数在第一个 await 之前抛出异常时 V8 已经通知过 rejection, 要从跟踪器中移除
+        if let Ok(promise) = result.try_cast::<v8::Promise>() {
+            promise.mark_as_handled();
+            self.rejection_tracker.forget(promise);
         }
 
-        let undefined = v8::undefined(scope); // 创建 undefined 值
+        // 运行事件循环，处理所有异步任务，所有任务完成后返回
+        self.task_dispatcher.run_event_loop(scope).await?;
 
-        // 调用 main 函数（绑定 undefined 为函数的 this 参数，&[] 为参数列表）
-        let result = main_fn
-            .cast::<v8::Function>()
-            .call(scope, undefined.into(), &[])
-            .unwrap();
+        // 入口函数是 async 函数时, 取出 Promise 完成后的值
+        let result = match result.try_cast::<v8::Promise>() {
+            Ok(promise) => match promise.state() {
+                v8::PromiseState::Fulfilled => promise.result(scope),
+                v8::PromiseState::Rejected => {
+                    let exception = promise.result(scope);
+                    return Err(JsError::Evaluate(JsException::from_exception(
+                        scope, exception, None,
+                    )));
+                }
+                // 事件循环已经结束, 没有任何任务能让这个 Promise 完成了
+                v8::PromiseState::Pending => {
+                    return Err(JsError::Evaluate(JsException {
+                        message: "入口函数返回的 Promise 在事件循环结束时仍未完成".to_string(),
+                        ..Default::default()
+                    }));
+                }
+            },
+            Err(_) => result,
+        };
 
-        // 运行事件循环，处理所有异步任务
-        self.task_dispatcher
-            .run_event_loop(unsafe { &mut *isolate_ptr }, scope)
-            .await;
+        JsValue::from_v8(scope, result).map_err(JsError::Conversion) // 返回入口函数的执行结果
+    }
 
-        result // 返回 main 函数的执行结果
+    /// 实例化并执行模块，按选项调用入口函数
+    ///
+    /// # 返回
+    /// 返回入口函数的返回值（尚未等待 Promise 完成），不调用入口时返回 undefined
+    async fn evaluate_module<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        module: v8::Local<'s, v8::Module>,
+        options: ExecuteOptions,
+    ) -> Result<v8::Local<'s, v8::Value>, JsError> {
+        let evaluation = {
+            let tc_scope = &mut v8::TryCatch::new(scope); // 捕获实例化和执行过程中抛出的异常
+
+            // 实例化模块, 解析所有静态 import 的依赖
+            if module
+                .instantiate_module(tc_scope, resolve_module_callback)
+                .is_none()
+            {
+                return Err(JsError::Instantiate(JsException::from_try_catch(tc_scope)));
+            }
+
+            // 执行模块（顶级代码），主要用于: 执行模块的顶层代码（变量声明、初始化等）、处理模块的导入/导出、但不会自动调用导出的函数
+            match module.evaluate(tc_scope) {
+                Some(evaluation) => evaluation,
+                None => return Err(JsError::Evaluate(JsException::from_try_catch(tc_scope))),
+            }
+        };
+
+        // 模块执行返回 Promise, 模块（或它的依赖）中有顶层 await 时要由事件循环推进它完成
+        if let Ok(evaluation) = evaluation.try_cast::<v8::Promise>() {
+            // 执行失败会转换为 JsError, 不算作未处理的 rejection;
+            // 顶层代码同步抛出异常时 V8 已经通知过 rejection, 要从跟踪器中移除
+            evaluation.mark_as_handled();
+            self.rejection_tracker.forget(evaluation);
+            self.task_dispatcher
+                .run_event_loop_until(scope, evaluation)
+                .await?;
+
+            if evaluation.state() == v8::PromiseState::Pending {
+                return Err(JsError::Evaluate(JsException {
+                    message: "模块的顶层 await 在事件循环结束时仍未完成".to_string(),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        // 顶层代码抛出的异常（包括顶层 await 被 reject）不会被 TryCatch 捕获, 而是让模块进入 Errored 状态
+        if module.get_status() == v8::ModuleStatus::Errored {
+            let exception = module.get_exception();
+            return Err(JsError::Evaluate(JsException::from_exception(
+                scope, exception, None,
+            )));
+        }
+
+        // 保存模块导出的命名空间, 之后可以通过 call_export 调用导出的函数
+        let module_namespace = module.get_module_namespace().cast::<v8::Object>(); // 获取 js 模块导出的命名空间
+        self.module_namespace = Some(v8::Global::new(scope, module_namespace));
+
+        // 只执行顶层代码时没有入口函数可调用
+        match options.entrypoint.export_name() {
+            None => Ok(v8::undefined(scope).into()),
+            Some(entry_fn_name) => {
+                call_exported_function(scope, module_namespace, entry_fn_name, options.args)
+            }
+        }
     }
 }
 
-// TODO - 待完成的动态 import() 处理
-/// 处理动态 import() 的回调函数
+/// 调用模块导出的函数
 ///
 /// # 参数
-/// - `scope`: V8 作用域，用于 GC 跟踪
-/// - `host_defined_options`: 主机定义的选项
-/// - `resource_name`: 资源名称（通常是文件名）
-/// - `specifier`: 模块标识符（import() 中的字符串）
-/// - `import_assertions`: import 断言（ES2023 功能）
-fn host_import_module_dynamically_callback_example<'s>(
-    _scope: &mut v8::HandleScope<'s>,
-    _host_defined_options: v8::Local<'s, v8::Data>,
-    _resource_name: v8::Local<'s, v8::Value>,
-    _specifier: v8::Local<'s, v8::String>,
-    _import_assertions: v8::Local<'s, v8::FixedArray>,
-) -> Option<v8::Local<'s, v8::Promise>> {
-    todo!() // 标记为未实现
+/// - `scope`: V8 作用域
+/// - `module_namespace`: 模块导出的命名空间
+/// - `name`: 导出的函数名称
+/// - `args`: 传给函数的参数
+///
+/// # 返回
+/// 返回函数的返回值（尚未等待 Promise 完成）
+fn call_exported_function<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    module_namespace: v8::Local<'s, v8::Object>,
+    name: &str,
+    args: Vec<JsValue>,
+) -> Result<v8::Local<'s, v8::Value>, JsError> {
+    let tc_scope = &mut v8::TryCatch::new(scope); // 捕获函数抛出的异常
+    let entry_fn_key = v8::String::new(tc_scope, name).unwrap();
+
+    // 获取导出的函数, 检查是否确实是函数
+    let Some(entry_fn) = module_namespace
+        .get(tc_scope, entry_fn_key.into())
+        .and_then(|entry_fn| entry_fn.try_cast::<v8::Function>().ok())
+    else {
+        return Err(JsError::MissingEntrypoint(name.to_string()));
+    };
+
+    let undefined = v8::undefined(tc_scope); // 创建 undefined 值
+    let args = args
+        .into_iter()
+        .map(|arg| arg.into_v8(tc_scope)) // 将 Rust 值转换为 V8 值
+        .collect::<Vec<_>>();
+
+    // 调用函数（绑定 undefined 为函数的 this 参数）
+    match entry_fn.call(tc_scope, undefined.into(), &args) {
+        Some(result) => Ok(result),
+        None => Err(JsError::Evaluate(JsException::from_try_catch(tc_scope))),
+    }
+}
+
+/// 要执行的入口
+enum EntrySource<'a> {
+    File(&'a str),                             // 入口模块的文件路径
+    Module { name: &'a str, source: &'a str }, // 内存中的 ES 模块（虚拟路径、源代码）
+    Script(&'a str),                           // 内存中的经典脚本源代码
+    Export(&'a str),                           // 之前执行的入口模块导出的函数名称
 }
@@ -0,0 +1,280 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt, future::Future};
+
+use crate::builtin::async_task::{
+    create_async_task_from_scope, event_loop_handle_from_scope, io_error_code, AsyncTaskResult,
+    AsyncTaskValue, EventLoopHandle,
+};
+use crate::serde_v8;
+
+/// 把普通的 Rust 函数包装为 V8 函数回调
+///
+/// 参数通过 serde 从 JS 值反序列化，类型不匹配时抛出 TypeError；
+/// 返回值必须是 `Result<T, E>`，T 通过 serde 序列化为 JS 值，E 转换为 OpError 后抛出为 Error 实例。
+/// `async fn` 返回 Promise，由异步任务调度器执行，完成后 resolve 或 reject
+///
+/// # 示例
+/// ```ignore
+/// zjs::op! {
+///     fn add(a: f64, b: f64) -> Result<f64, String> {
+///         Ok(a + b)
+///     }
+/// }
+///
+/// zjs::op! {
+///     async fn read_text(path: String) -> Result<String, std::io::Error> {
+///         tokio::fs::read_to_string(path).await
+///     }
+/// }
+///
+/// let runtime = JsRuntime::with_options(RuntimeOptions {
+///     ops: vec![OpFunction::new("add", add), OpFunction::new("readText", read_text)],
+///     ..Default::default()
+/// });
+/// ```
+#[macro_export]
+macro_rules! op {
+    (
+        $(#[$meta:meta])*
+        $vis:vis async fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block
+    ) => {
+        $(#[$meta])*
+        $vis fn $name(
+            scope: &mut $crate::v8::HandleScope,
+            #[allow(unused_variables)] args: $crate::v8::FunctionCallbackArguments, // 没有参数的 op 不读取
+            mut return_value: $crate::v8::ReturnValue,
+        ) {
+            async fn op_impl($($arg: $ty),*) -> $ret $body
+
+            #[allow(unused_mut, unused_variables)]
+            let mut indices = 0..; // 参数的下标, 没有参数的 op 不使用
+            $(
+                let Some($arg) = $crate::op::decode_arg::<$ty>(
+                    scope,
+                    &args,
+                    indices.next().unwrap(),
+                    stringify!($arg),
+                ) else {
+                    return; // 参数不合法, 已经抛出 TypeError
+                };
+            )*
+
+            $crate::op::spawn_result(scope, &mut return_value, op_impl($($arg),*));
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block
+    ) => {
+        $(#[$meta])*
+        $vis fn $name(
+            scope: &mut $crate::v8::HandleScope,
+            #[allow(unused_variables)] args: $crate::v8::FunctionCallbackArguments, // 没有参数的 op 不读取
+            mut return_value: $crate::v8::ReturnValue,
+        ) {
+            fn op_impl($($arg: $ty),*) -> $ret $body
+
+            #[allow(unused_mut, unused_variables)]
+            let mut indices = 0..; // 参数的下标, 没有参数的 op 不使用
+            $(
+                let Some($arg) = $crate::op::decode_arg::<$ty>(
+                    scope,
+                    &args,
+                    indices.next().unwrap(),
+                    stringify!($arg),
+                ) else {
+                    return; // 参数不合法, 已经抛出 TypeError
+                };
+            )*
+
+            $crate::op::set_result(scope, &mut return_value, op_impl($($arg),*));
+        }
+    };
+}
+
+/// 通过 op! 定义的函数及其在 JS 中的名称
+pub struct OpFunction {
+    pub(crate) name: String,                   // JS 中的名称
+    pub(crate) callback: v8::FunctionCallback, // V8 函数回调
+}
+
+impl OpFunction {
+    /// 创建 OpFunction
+    ///
+    /// # 参数
+    /// - `name`: JS 中的名称
+    /// - `callback`: 通过 op! 定义的函数（或其他 V8 函数回调）
+    pub fn new(name: impl Into<String>, callback: impl v8::MapFnTo<v8::FunctionCallback>) -> Self {
+        Self {
+            name: name.into(),
+            callback: callback.map_fn_to(),
+        }
+    }
+}
+
+/// op 返回的错误，在 JS 中抛出（或 reject）为 Error 实例
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpError {
+    pub name: String,         // 错误类型，例如 Error、TypeError
+    pub code: Option<String>, // 错误码，例如 ENOENT
+    pub message: String,      // 错误信息
+}
+
+impl OpError {
+    /// 创建 Error
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            name: "Error".to_string(),
+            code: None,
+            message: message.into(),
+        }
+    }
+
+    /// 创建 TypeError
+    pub fn type_error(message: impl Into<String>) -> Self {
+        Self {
+            name: "TypeError".to_string(),
+            ..Self::new(message)
+        }
+    }
+
+    /// 设置错误码
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+}
+
+impl fmt::Display for OpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.message)
+    }
+}
+
+impl std::error::Error for OpError {}
+
+impl From<String> for OpError {
+    fn from(message: String) -> Self {
+        OpError::new(message)
+    }
+}
+
+impl From<&str> for OpError {
+    fn from(message: &str) -> Self {
+        OpError::new(message)
+    }
+}
+
+impl From<std::io::Error> for OpError {
+    // 带上 ENOENT 等错误码, 与 Node 的文件系统错误一致
+    fn from(error: std::io::Error) -> Self {
+        match io_error_code(&error) {
+            Some(code) => OpError::new(format!("{}: {}", code, error)).with_code(code),
+            None => OpError::new(error.to_string()),
+        }
+    }
+}
+
+impl From<serde_v8::Error> for OpError {
+    fn from(error: serde_v8::Error) -> Self {
+        OpError::type_error(error.to_string())
+    }
+}
+
+impl From<OpError> for AsyncTaskValue {
+    fn from(error: OpError) -> Self {
+        AsyncTaskValue::Error {
+            name: error.name,
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
+/// 从函数参数中反序列化第 index 个参数，失败时抛出 TypeError 并返回 None
+#[doc(hidden)]
+pub fn decode_arg<T: DeserializeOwned>(
+    scope: &mut v8::HandleScope,
+    args: &v8::FunctionCallbackArguments,
+    index: i32,
+    name: &str,
+) -> Option<T> {
+    match serde_v8::from_v8(scope, args.get(index)) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            let message = format!("The \"{}\" argument is invalid: {}", name, e);
+            let error = OpError::type_error(message).with_code("ERR_INVALID_ARG_TYPE");
+            throw_op_error(scope, error);
+            None
+        }
+    }
+}
+
+/// 设置同步 op 的返回值，失败时抛出异常
+#[doc(hidden)]
+pub fn set_result<T: Serialize, E: Into<OpError>>(
+    scope: &mut v8::HandleScope,
+    return_value: &mut v8::ReturnValue,
+    result: Result<T, E>,
+) {
+    let result = result
+        .map_err(Into::into)
+        .and_then(|value| serde_v8::to_v8(scope, &value).map_err(OpError::from));
+
+    match result {
+        Ok(value) => return_value.set(value),
+        Err(error) => throw_op_error(scope, error),
+    }
+}
+
+/// 把异步 op 交给异步任务调度器执行，返回值设置为对应的 Promise
+#[doc(hidden)]
+pub fn spawn_result<T, E, F>(
+    scope: &mut v8::HandleScope,
+    return_value: &mut v8::ReturnValue,
+    future: F,
+) where
+    T: Serialize + 'static,
+    E: Into<OpError> + 'static,
+    F: Future<Output = Result<T, E>> + Send + 'static,
+{
+    let promise = create_async_task_from_scope(scope, async move {
+        // 在任务所在的线程中序列化结果, 事件循环只需要把 AsyncTaskValue 转换为 V8 值
+        let result = future
+            .await
+            .map_err(Into::into)
+            .and_then(|value| AsyncTaskValue::serialize(&value).map_err(OpError::from));
+
+        match result {
+            Ok(value) => AsyncTaskResult::Resolve(value),
+            Err(error) => AsyncTaskResult::Reject(error.into()),
+        }
+    });
+
+    return_value.set(promise.into());
+}
+
+/// 在 V8 函数回调中获取当前运行时的事件循环句柄引用计数
+///
+/// 回调创建长生命周期的句柄时 ref，把返回的句柄移动到异步任务中，关闭时 unref
+///
+/// # 示例
+/// ```ignore
+/// fn listen(scope: &mut v8::HandleScope, _args: v8::FunctionCallbackArguments, _rv: v8::ReturnValue) {
+///     let handle = zjs::op::event_loop_handle(scope);
+///     handle.ref_handle();
+///     tokio::spawn(async move {
+///         // ... 监听直到关闭
+///         handle.unref_handle();
+///     });
+/// }
+/// ```
+pub fn event_loop_handle(scope: &mut v8::HandleScope) -> EventLoopHandle {
+    event_loop_handle_from_scope(scope)
+}
+
+/// 在 JS 端抛出 OpError
+fn throw_op_error(scope: &mut v8::HandleScope, error: OpError) {
+    let exception = AsyncTaskValue::from(error).into_v8(scope);
+    scope.throw_exception(exception);
+}
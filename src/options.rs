@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::builtin::async_task::TokioAsyncTaskManager;
+use crate::builtin::registry::BuiltinModule;
+use crate::op::OpFunction;
+use crate::serde_v8;
+use crate::unhandled_rejection::UnhandledRejectionPolicy;
+use crate::value::JsValue;
+
+/// 执行模块后要调用的入口
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entrypoint {
+    Named(String), // 调用指定名称的导出函数
+    Default,       // 调用 default 导出的函数
+    None,          // 只执行模块的顶层代码，不调用任何导出
+}
+
+impl Entrypoint {
+    /// 入口对应的导出名称，不调用导出时返回 None
+    pub(crate) fn export_name(&self) -> Option<&str> {
+        match self {
+            Entrypoint::Named(name) => Some(name),
+            Entrypoint::Default => Some("default"),
+            Entrypoint::None => None,
+        }
+    }
+}
+
+/// 运行时自带的内置模块
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Builtin {
+    Fs,     // fs（也可以通过 node:fs 导入）
+    Module, // module（createRequire）
+}
+
+impl Builtin {
+    /// 全部自带的内置模块
+    pub const ALL: [Builtin; 2] = [Builtin::Fs, Builtin::Module];
+}
+
+/// 可以注入到全局对象的 API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlobalApi {
+    Print, // print(...args)
+}
+
+impl GlobalApi {
+    /// 全部可以注入的全局 API
+    pub const ALL: [GlobalApi; 1] = [GlobalApi::Print];
+}
+
+/// JsRuntime::execute_with_options 的执行选项
+///
+/// 参数可以是任意实现了 Serialize 的值，通过 serde_v8 转换为 JS 值，
+/// 无法序列化的参数在执行时返回 JsError::Conversion
+///
+/// # 示例
+/// ```ignore
+/// let options = ExecuteOptions::new().entrypoint("handler").arg("hello").arg(1);
+/// runtime.execute_with_options("./main.js", options).await?;
+/// ```
+#[derive(Debug)]
+pub struct ExecuteOptions {
+    pub(crate) entrypoint: Entrypoint,             // 入口
+    pub(crate) args: Vec<JsValue>,                 // 传给入口函数的参数
+    pub(crate) arg_error: Option<serde_v8::Error>, // 第一个无法序列化的参数的错误
+}
+
+impl Default for ExecuteOptions {
+    // 默认调用导出的 main 函数，不传参数
+    fn default() -> Self {
+        Self {
+            entrypoint: Entrypoint::Named("main".to_string()),
+            args: Vec::new(),
+            arg_error: None,
+        }
+    }
+}
+
+impl ExecuteOptions {
+    /// 创建默认的执行选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 调用指定名称的导出函数
+    pub fn entrypoint(mut self, name: impl Into<String>) -> Self {
+        self.entrypoint = Entrypoint::Named(name.into());
+        self
+    }
+
+    /// 调用 default 导出的函数
+    pub fn default_export(mut self) -> Self {
+        self.entrypoint = Entrypoint::Default;
+        self
+    }
+
+    /// 只执行模块的顶层代码，不调用任何导出
+    pub fn no_entrypoint(mut self) -> Self {
+        self.entrypoint = Entrypoint::None;
+        self
+    }
+
+    /// 追加一个传给入口函数的参数
+    pub fn arg(mut self, value: impl Serialize) -> Self {
+        match serde_v8::to_value(&value) {
+            Ok(value) => self.args.push(value),
+            Err(error) => {
+                self.arg_error.get_or_insert(error);
+            }
+        }
+        self
+    }
+
+    /// 追加多个传给入口函数的参数
+    pub fn args<T: Serialize>(self, values: impl IntoIterator<Item = T>) -> Self {
+        values.into_iter().fold(self, Self::arg)
+    }
+}
+
+/// JsRuntime::with_options 的运行时选项
+///
+/// # 示例
+/// ```ignore
+/// let runtime = JsRuntime::with_options(RuntimeOptions {
+///     max_heap_size: Some(64 * 1024 * 1024),
+///     v8_flags: vec!["--stack-size=2048".to_string()],
+///     ..Default::default()
+/// });
+/// ```
+pub struct RuntimeOptions {
+    // 初始堆大小（字节），只在设置了 max_heap_size 时生效
+    pub initial_heap_size: usize,
+    // 最大堆大小（字节），None 表示使用 V8 的默认值
+    pub max_heap_size: Option<usize>,
+    // 堆即将耗尽时，为了终止脚本并清理而临时增加一次的堆空间（字节），None 表示与当时的堆上限相同
+    pub heap_limit_extension: Option<usize>,
+    // V8 命令行参数，例如 "--expose-gc"，V8 参数对整个进程生效，
+    // 只有进程中第一个创建的 JsRuntime 的参数生效，之后的 JsRuntime 的参数会被忽略
+    pub v8_flags: Vec<String>,
+    // 每次 execute 的执行时间上限，超时后终止脚本并返回 JsError::Terminated，None 表示不限制
+    pub execution_timeout: Option<Duration>,
+    // 异步任务调度器，op 通过隔离区中保存的指针访问它
+    pub task_dispatcher: TokioAsyncTaskManager,
+    // 允许 import 的自带内置模块
+    pub builtin_modules: Vec<Builtin>,
+    // 嵌入方注册的内置模块，可以通过名称 import（例如 "app:db"）
+    pub extensions: Vec<BuiltinModule>,
+    // 注入到全局对象的 API
+    pub globals: Vec<GlobalApi>,
+    // 注入到全局对象的 op 函数（通过 op! 定义）
+    pub ops: Vec<OpFunction>,
+    // 未处理的 Promise rejection 的处理策略
+    pub unhandled_rejection_policy: UnhandledRejectionPolicy,
+}
+
+impl Default for RuntimeOptions {
+    // 默认注入全部内置模块和全局 API
+    fn default() -> Self {
+        Self {
+            initial_heap_size: 0,
+            max_heap_size: None,
+            heap_limit_extension: None,
+            v8_flags: Vec::new(),
+            execution_timeout: None,
+            task_dispatcher: TokioAsyncTaskManager::default(),
+            builtin_modules: Builtin::ALL.to_vec(),
+            extensions: Vec::new(),
+            globals: GlobalApi::ALL.to_vec(),
+            ops: Vec::new(),
+            unhandled_rejection_policy: UnhandledRejectionPolicy::default(),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use indexmap::IndexMap;
+use serde::de::{
+    self, Deserialize, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+
+use super::Error;
+use crate::value::JsValue;
+
+impl<'de> de::Deserializer<'de> for JsValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsValue::Undefined | JsValue::Null => visitor.visit_unit(),
+            JsValue::Bool(value) => visitor.visit_bool(value),
+            // 整数交给 visitor 按整数处理, 这样可以反序列化为 u32、i64 等整数类型
+            // 非负整数可以一直到 u64::MAX, 负整数到 i64::MIN
+            JsValue::Number(value)
+                if value.fract() == 0.0 && value >= -(2f64.powi(63)) && value < 2f64.powi(64) =>
+            {
+                if value >= 0.0 {
+                    visitor.visit_u64(value as u64)
+                } else {
+                    visitor.visit_i64(value as i64)
+                }
+            }
+            JsValue::Number(value) => visitor.visit_f64(value),
+            JsValue::String(value) => visitor.visit_string(value),
+            JsValue::BigInt(value) => match i64::try_from(value) {
+                Ok(value) => visitor.visit_i64(value),
+                Err(_) => visitor.visit_i128(value),
+            },
+            JsValue::Bytes(value) => visitor.visit_byte_buf(value),
+            JsValue::Array(elements) => visitor.visit_seq(ArrayDeserializer {
+                elements: elements.into_iter(),
+            }),
+            JsValue::Object(properties) => visitor.visit_map(ObjectDeserializer {
+                properties: properties.into_iter(),
+                value: None,
+            }),
+        }
+    }
+
+    // Vec<u8>、[u8; N] 等按序列反序列化, Uint8Array 的内容逐个作为 u8 元素
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsValue::Bytes(bytes) => visitor.visit_seq(ArrayDeserializer {
+                elements: bytes
+                    .into_iter()
+                    .map(|byte| JsValue::Number(byte.into()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    // undefined 和 null 都表示没有值
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsValue::Undefined | JsValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // 单元变体来自字符串, 其他变体来自只有一个属性的对象 `{ 变体名: 值 }`
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            JsValue::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            JsValue::Object(properties) if properties.len() == 1 => {
+                let (variant, value) = properties.into_iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: Some(value),
+                })
+            }
+            _ => Err(Error("枚举必须是字符串或只有一个属性的对象".to_string())),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for JsValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// 逐个反序列化数组的元素
+struct ArrayDeserializer {
+    elements: std::vec::IntoIter<JsValue>, // 剩余的元素
+}
+
+impl<'de> SeqAccess<'de> for ArrayDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.elements.next() {
+            Some(element) => seed.deserialize(element).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+/// 逐个反序列化对象的属性
+struct ObjectDeserializer {
+    properties: indexmap::map::IntoIter<String, JsValue>, // 剩余的属性
+    value: Option<JsValue>,                               // 已经取出键、等待反序列化的值
+}
+
+impl<'de> MapAccess<'de> for ObjectDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.properties.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(PropertyKeyDeserializer(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error("调用 next_value_seed 之前没有调用 next_key_seed".to_string()))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.properties.len())
+    }
+}
+
+/// 反序列化对象的属性名
+///
+/// JS 对象的属性名总是字符串，反序列化为数字或布尔值（例如 `HashMap<u32, _>` 的键）时先解析字符串
+struct PropertyKeyDeserializer(String);
+
+/// 把属性名解析为指定类型后交给 visitor
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse() {
+                    Ok(key) => visitor.$visit(key),
+                    Err(_) => Err(Error(format!("无法把属性名 '{}' 解析为所需的类型", self.0))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for PropertyKeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        JsValue::String(self.0).deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// 反序列化枚举变体
+struct EnumDeserializer {
+    variant: String,        // 变体名
+    value: Option<JsValue>, // 变体的值（单元变体没有值）
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantDeserializer), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+/// 反序列化枚举变体的值
+struct VariantDeserializer {
+    value: Option<JsValue>, // 变体的值
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.value {
+            None | Some(JsValue::Undefined) | Some(JsValue::Null) => Ok(()),
+            Some(_) => Err(Error("单元变体不能有值".to_string())),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(Error("newtype 变体缺少值".to_string())),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Some(value @ JsValue::Array(_)) => de::Deserializer::deserialize_any(value, visitor),
+            _ => Err(Error("元组变体的值必须是数组".to_string())),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Some(value @ JsValue::Object(_)) => de::Deserializer::deserialize_any(value, visitor),
+            _ => Err(Error("结构体变体的值必须是对象".to_string())),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JsValue {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsValueVisitor)
+    }
+}
+
+/// 把任意 serde 数据模型中的值转换为 JsValue
+struct JsValueVisitor;
+
+impl<'de> Visitor<'de> for JsValueVisitor {
+    type Value = JsValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("任意 JS 值")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<JsValue, E> {
+        Ok(JsValue::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<JsValue, E> {
+        Ok(super::ser::integer(value.into()))
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<JsValue, E> {
+        Ok(super::ser::integer(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<JsValue, E> {
+        Ok(super::ser::integer(value.into()))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<JsValue, E> {
+        i128::try_from(value)
+            .map(super::ser::integer)
+            .map_err(|_| E::custom(format!("整数 {} 超出了 BigInt 支持的范围", value)))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<JsValue, E> {
+        Ok(JsValue::Number(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<JsValue, E> {
+        Ok(JsValue::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<JsValue, E> {
+        Ok(JsValue::String(value))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<JsValue, E> {
+        Ok(JsValue::Bytes(value.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<JsValue, E> {
+        Ok(JsValue::Bytes(value))
+    }
+
+    fn visit_none<E>(self) -> Result<JsValue, E> {
+        Ok(JsValue::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<JsValue, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<JsValue, E> {
+        Ok(JsValue::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsValue, A::Error> {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(JsValue::Array(elements))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsValue, A::Error> {
+        let mut properties = IndexMap::new();
+        while let Some((key, value)) = map.next_entry::<String, JsValue>()? {
+            properties.insert(key, value);
+        }
+        Ok(JsValue::Object(properties))
+    }
+}
@@ -0,0 +1,69 @@
+mod de;
+mod ser;
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+
+use crate::value::JsValue;
+
+pub use ser::Serializer;
+
+/// 序列化或反序列化失败时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error(message.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error(message.to_string())
+    }
+}
+
+/// 将 Rust 值序列化为 JsValue
+///
+/// JsValue 不依赖 V8 作用域，异步任务可以在其他线程中先序列化，再交给事件循环转换为 V8 值
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, Error> {
+    value.serialize(Serializer)
+}
+
+/// 将 JsValue 反序列化为 Rust 值
+pub fn from_value<T: DeserializeOwned>(value: JsValue) -> Result<T, Error> {
+    T::deserialize(value)
+}
+
+/// 将 Rust 值序列化为 V8 值（先序列化为 JsValue 再转换）
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `value`: 要序列化的值
+pub fn to_v8<'s, T: Serialize + ?Sized>(
+    scope: &mut v8::HandleScope<'s>,
+    value: &T,
+) -> Result<v8::Local<'s, v8::Value>, Error> {
+    Ok(to_value(value)?.into_v8(scope))
+}
+
+/// 将 V8 值反序列化为 Rust 值（先转换为 JsValue 再反序列化）
+///
+/// # 参数
+/// - `scope`: V8 作用域
+/// - `value`: 要反序列化的 V8 值
+pub fn from_v8<T: DeserializeOwned>(
+    scope: &mut v8::HandleScope<'_>,
+    value: v8::Local<'_, v8::Value>,
+) -> Result<T, Error> {
+    from_value(JsValue::from_v8(scope, value)?)
+}
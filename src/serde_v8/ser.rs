@@ -0,0 +1,382 @@
+use indexmap::IndexMap;
+use serde::ser::{self, Serialize};
+
+use super::Error;
+use crate::value::JsValue;
+
+/// JS 中可以精确表示的最大整数（Number.MAX_SAFE_INTEGER）
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+/// 将 Rust 值序列化为 JsValue 的 Serializer
+///
+/// - 数字统一为 f64，超出安全整数范围的整数转换为 BigInt
+/// - 字节（serialize_bytes）转换为 Uint8Array
+/// - Option::None 和 () 转换为 null
+/// - 结构体和 map 转换为对象，序列和元组转换为数组
+/// - 单元枚举变体转换为字符串，其他变体转换为 `{ 变体名: 值 }`
+pub struct Serializer;
+
+/// 整数在安全范围内时转换为数字，否则转换为 BigInt
+pub(super) fn integer(value: i128) -> JsValue {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value) {
+        JsValue::Number(value as f64)
+    } else {
+        JsValue::BigInt(value)
+    }
+}
+
+/// 把序列化后的 map 键转换为对象的属性名，只支持字符串、数字和布尔值
+fn property_key(key: JsValue) -> Result<String, Error> {
+    match key {
+        JsValue::String(key) => Ok(key),
+        JsValue::Number(key) if key.fract() == 0.0 && key.abs() < 1e21 => {
+            Ok((key as i64).to_string())
+        }
+        JsValue::Number(key) => Ok(key.to_string()),
+        JsValue::BigInt(key) => Ok(key.to_string()),
+        JsValue::Bool(key) => Ok(key.to_string()),
+        _ => Err(Error("对象的键必须是字符串、数字或布尔值".to_string())),
+    }
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = JsValue;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, value: bool) -> Result<JsValue, Error> {
+        Ok(JsValue::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<JsValue, Error> {
+        Ok(integer(value.into()))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<JsValue, Error> {
+        Ok(integer(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<JsValue, Error> {
+        Ok(integer(value.into()))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<JsValue, Error> {
+        i128::try_from(value)
+            .map(integer)
+            .map_err(|_| Error(format!("整数 {} 超出了 BigInt 支持的范围", value)))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value.into()))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<JsValue, Error> {
+        Ok(JsValue::Number(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<JsValue, Error> {
+        Ok(JsValue::String(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<JsValue, Error> {
+        Ok(JsValue::String(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<JsValue, Error> {
+        Ok(JsValue::Bytes(value.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<JsValue, Error> {
+        Ok(JsValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JsValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<JsValue, Error> {
+        Ok(JsValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JsValue, Error> {
+        Ok(JsValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<JsValue, Error> {
+        Ok(JsValue::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JsValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JsValue, Error> {
+        let mut properties = IndexMap::new();
+        properties.insert(variant.to_string(), value.serialize(self)?);
+        Ok(JsValue::Object(properties))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant, Error> {
+        Ok(SerializeTupleVariant {
+            variant,
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject {
+            properties: IndexMap::new(),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeStructVariant, Error> {
+        Ok(SerializeStructVariant {
+            variant,
+            properties: IndexMap::new(),
+        })
+    }
+}
+
+/// 序列化为数组（序列、元组、元组结构体）
+pub struct SerializeArray {
+    elements: Vec<JsValue>, // 已序列化的元素
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.elements.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        Ok(JsValue::Array(self.elements))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// 序列化元组变体为 `{ 变体名: [...] }`
+pub struct SerializeTupleVariant {
+    variant: &'static str,  // 变体名
+    elements: Vec<JsValue>, // 已序列化的元素
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.elements.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        let mut properties = IndexMap::new();
+        properties.insert(self.variant.to_string(), JsValue::Array(self.elements));
+        Ok(JsValue::Object(properties))
+    }
+}
+
+/// 序列化为对象（map、结构体）
+pub struct SerializeObject {
+    properties: IndexMap<String, JsValue>, // 已序列化的属性
+    next_key: Option<String>,              // 等待值的键
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.next_key = Some(property_key(key.serialize(Serializer)?)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error("调用 serialize_value 之前没有调用 serialize_key".to_string()))?;
+        self.properties.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        Ok(JsValue::Object(self.properties))
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.properties
+            .insert(key.to_string(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        Ok(JsValue::Object(self.properties))
+    }
+}
+
+/// 序列化结构体变体为 `{ 变体名: { ... } }`
+pub struct SerializeStructVariant {
+    variant: &'static str,                 // 变体名
+    properties: IndexMap<String, JsValue>, // 已序列化的属性
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = JsValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.properties
+            .insert(key.to_string(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsValue, Error> {
+        let mut properties = IndexMap::new();
+        properties.insert(self.variant.to_string(), JsValue::Object(self.properties));
+        Ok(JsValue::Object(properties))
+    }
+}
+
+impl Serialize for JsValue {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsValue::Undefined | JsValue::Null => serializer.serialize_unit(),
+            JsValue::Bool(value) => serializer.serialize_bool(*value),
+            // 安全范围内的整数按整数序列化, 避免其他格式（如 JSON）输出 1.0
+            JsValue::Number(value)
+                if value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER as f64 =>
+            {
+                serializer.serialize_i64(*value as i64)
+            }
+            JsValue::Number(value) => serializer.serialize_f64(*value),
+            JsValue::String(value) => serializer.serialize_str(value),
+            JsValue::BigInt(value) => serializer.serialize_i128(*value),
+            JsValue::Bytes(value) => serializer.serialize_bytes(value),
+            JsValue::Array(elements) => serializer.collect_seq(elements),
+            JsValue::Object(properties) => serializer.collect_map(properties),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+/// 终止信号，在终止句柄的所有克隆之间共享
+struct TerminationSignal {
+    terminated: AtomicBool, // 是否已请求终止
+    notify: Notify,         // 唤醒正在等待事件循环的 execute
+}
+
+/// 线程安全的终止句柄
+///
+/// 可以克隆并发送到其他线程，用于终止 JsRuntime 中正在运行的 execute，
+/// 被终止的 execute 返回 JsError::Terminated，之后 JsRuntime 可以继续使用或直接销毁
+#[derive(Clone)]
+pub struct TerminationHandle {
+    isolate_handle: v8::IsolateHandle, // V8 隔离区的线程安全句柄
+    signal: Arc<TerminationSignal>,    // 终止信号
+}
+
+impl TerminationHandle {
+    /// 根据 V8 隔离区的线程安全句柄创建终止句柄
+    pub(crate) fn new(isolate_handle: v8::IsolateHandle) -> Self {
+        Self {
+            isolate_handle,
+            signal: Arc::new(TerminationSignal {
+                terminated: AtomicBool::new(false),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// 终止正在运行的脚本
+    ///
+    /// 正在执行的 JS 代码会立即停止，正在等待异步任务的事件循环也会被唤醒并退出
+    ///
+    /// # 返回
+    /// V8 隔离区已经被销毁时返回 false
+    pub fn terminate(&self) -> bool {
+        self.signal.terminated.store(true, Ordering::SeqCst);
+        self.signal.notify.notify_waiters(); // 唤醒等待中的事件循环
+        self.isolate_handle.terminate_execution() // 停止正在执行的 JS 代码
+    }
+
+    /// 是否已请求终止
+    pub fn is_terminated(&self) -> bool {
+        self.signal.terminated.load(Ordering::SeqCst)
+    }
+
+    /// 清除终止状态，每次 execute 开始前调用，之前的终止请求不影响新的执行
+    pub(crate) fn reset(&self) {
+        self.signal.terminated.store(false, Ordering::SeqCst);
+        self.isolate_handle.cancel_terminate_execution();
+    }
+
+    /// 取出并清除终止状态
+    pub(crate) fn take_terminated(&self) -> bool {
+        self.signal.terminated.swap(false, Ordering::SeqCst)
+    }
+
+    /// 等待直到请求终止
+    pub(crate) async fn terminated(&self) {
+        loop {
+            // 先创建 Notified 再检查状态, 避免错过检查之后发出的通知
+            let notified = self.signal.notify.notified();
+            if self.is_terminated() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// 执行超时的看门狗
+///
+/// 在独立的线程中计时（脚本死循环时当前线程无法调度任何异步任务），超时后终止执行，
+/// 在超时之前被 drop 时线程直接退出。
+/// 看门狗线程持有锁检查"已结束"标记并终止执行，所以 drop 返回之后不会再终止执行
+pub(crate) struct Watchdog {
+    done: Arc<(Mutex<bool>, Condvar)>, // 执行是否已经结束, drop 时设置并唤醒看门狗线程
+}
+
+impl Watchdog {
+    /// 启动看门狗线程
+    ///
+    /// # 参数
+    /// - `termination_handle`: 超时后用于终止执行的句柄
+    /// - `timeout`: 执行的时间上限
+    pub(crate) fn start(termination_handle: TerminationHandle, timeout: Duration) -> Self {
+        let done = Arc::new((Mutex::new(false), Condvar::new()));
+
+        thread::spawn({
+            let done = done.clone();
+            move || {
+                let (lock, condvar) = &*done;
+                let Ok(guard) = lock.lock() else {
+                    return;
+                };
+                let Ok((finished, _)) = condvar.wait_timeout_while(guard, timeout, |done| !*done)
+                else {
+                    return;
+                };
+                // 只有真正超时才终止, 终止时仍然持有锁, 执行不会在检查之后才被标记为结束
+                if !*finished {
+                    termination_handle.terminate();
+                }
+            }
+        });
+
+        Self { done }
+    }
+}
+
+impl Drop for Watchdog {
+    // 标记执行已经结束, 看门狗线程正在终止执行时等待它完成
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.done;
+        if let Ok(mut done) = lock.lock() {
+            *done = true;
+        }
+        condvar.notify_one();
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::{JsError, JsException};
+
+/// 未处理的 Promise rejection 的处理策略，对应 Node 的 unhandledRejection 行为
+#[derive(Default)]
+pub enum UnhandledRejectionPolicy {
+    #[default]
+    Warn, // 打印到 stderr 后继续运行（默认）
+    Error,                              // 让 execute 返回 JsError::UnhandledRejection
+    Hook(Box<dyn FnMut(&JsException)>), // 交给 Rust 回调处理
+}
+
+/// 未处理的 Promise rejection 跟踪器，存放在 V8 隔离区的 2 号插槽中
+///
+/// V8 在 Promise 被 reject 且没有处理函数时通知一次，之后如果又添加了处理函数会再通知一次，
+/// 所以要等到微任务队列清空后，仍然留在列表中的才是真正未处理的 rejection
+pub(crate) struct RejectionTracker {
+    pub(crate) policy: UnhandledRejectionPolicy, // 处理策略
+    pending: Vec<(v8::Global<v8::Promise>, v8::Global<v8::Value>)>, // 尚未被处理的 (Promise, reject 的值)
+}
+
+impl RejectionTracker {
+    /// 使用指定的处理策略创建跟踪器
+    pub(crate) fn new(policy: UnhandledRejectionPolicy) -> Self {
+        Self {
+            policy,
+            pending: Vec::new(),
+        }
+    }
+
+    /// 不再跟踪 Promise 的 rejection
+    ///
+    /// 用于由运行时自己处理结果的 Promise（入口函数和模块执行返回的 Promise），
+    /// 它们在 V8 通知之后才被标记为已处理，rejection 会作为 execute 的错误返回
+    pub(crate) fn forget(&mut self, promise: v8::Local<v8::Promise>) {
+        self.pending.retain(|(pending, _)| *pending != promise);
+    }
+
+    /// 清除所有尚未处理的 rejection，每次执行开始前调用，之前的执行留下的 rejection 不影响新的执行
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// 从 V8 隔离区中取出 RejectionTracker
+fn get_tracker<'a>(isolate: &v8::Isolate) -> Option<&'a mut RejectionTracker> {
+    let tracker_ptr = isolate.get_data(2);
+    if tracker_ptr.is_null() {
+        return None;
+    }
+    Some(unsafe { &mut *(tracker_ptr as *mut RejectionTracker) })
+}
+
+/// 不再跟踪 Promise 的 rejection，用于在回调中由运行时自己处理结果的 Promise
+pub(crate) fn forget_rejection(isolate: &v8::Isolate, promise: v8::Local<v8::Promise>) {
+    if let Some(tracker) = get_tracker(isolate) {
+        tracker.forget(promise);
+    }
+}
+
+/// Promise reject 回调函数
+///
+/// 记录没有处理函数的 rejection，在之后添加了处理函数时移除
+pub(crate) extern "C" fn promise_reject_callback(message: v8::PromiseRejectMessage) {
+    let scope = &mut unsafe { v8::CallbackScope::new(&message) }; // 创建作用域
+
+    let Some(tracker) = get_tracker(scope) else {
+        eprintln!("错误: 在 promise_reject_callback 中的 RejectionTracker 为空 ");
+        return;
+    };
+
+    let promise = message.get_promise();
+    match message.get_event() {
+        // 被 reject 时没有处理函数, 先记录下来
+        v8::PromiseRejectEvent::PromiseRejectWithNoHandler => {
+            let reason = message
+                .get_value()
+                .unwrap_or_else(|| v8::undefined(scope).into());
+            tracker.pending.push((
+                v8::Global::new(scope, promise),
+                v8::Global::new(scope, reason),
+            ));
+        }
+        // reject 之后又添加了处理函数, 不再是未处理的 rejection
+        v8::PromiseRejectEvent::PromiseHandlerAddedAfterReject => {
+            tracker.pending.retain(|(pending, _)| *pending != promise);
+        }
+        _ => {}
+    }
+}
+
+/// 按策略处理目前仍未处理的 rejection，在每次微任务检查点之后调用
+///
+/// # 返回
+/// 策略为 Error 时返回第一个未处理的 rejection
+pub(crate) fn process_unhandled_rejections(scope: &mut v8::HandleScope<'_>) -> Result<(), JsError> {
+    let Some(tracker) = get_tracker(scope) else {
+        return Ok(());
+    };
+
+    for (_, reason) in std::mem::take(&mut tracker.pending) {
+        let reason = v8::Local::new(scope, reason);
+        let exception = JsException::from_exception(scope, reason, None);
+
+        match &mut tracker.policy {
+            UnhandledRejectionPolicy::Warn => {
+                eprintln!("未处理的 Promise rejection: {}", exception);
+            }
+            UnhandledRejectionPolicy::Error => return Err(JsError::UnhandledRejection(exception)),
+            UnhandledRejectionPolicy::Hook(hook) => hook(&exception),
+        }
+    }
+
+    Ok(())
+}
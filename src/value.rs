@@ -0,0 +1,242 @@
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+
+use crate::error::JsException;
+use crate::serde_v8;
+
+/// 从 V8 值转换而来的 Rust 值
+///
+/// 与 v8::Local 不同，它不依赖任何 V8 作用域，可以在脚本执行结束后继续使用
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsValue {
+    Undefined,                         // undefined（函数、symbol 也转换为它）
+    Null,                              // null
+    Bool(bool),                        // 布尔值
+    Number(f64),                       // 数字
+    String(String),                    // 字符串
+    BigInt(i128),                      // BigInt（超出 i128 范围的按字符串表示）
+    Bytes(Vec<u8>),                    // 二进制数据（ArrayBuffer、Uint8Array 等）
+    Array(Vec<JsValue>),               // 数组
+    Object(IndexMap<String, JsValue>), // 对象（自身可枚举的属性，保持属性原来的顺序）
+}
+
+/// 对象和数组嵌套的最大深度，更深的值转换为 undefined，避免递归耗尽栈空间
+const MAX_DEPTH: usize = 128;
+
+impl JsValue {
+    /// 将 V8 值转换为 JsValue
+    ///
+    /// 循环引用（对象直接或间接引用自身）和超过 MAX_DEPTH 层的嵌套转换为 undefined
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `value`: 要转换的 V8 值
+    ///
+    /// # 返回
+    /// 读取属性时 getter 或 Proxy 抛出异常则返回错误，异常不会传到调用者的作用域中
+    pub(crate) fn from_v8(
+        scope: &mut v8::HandleScope<'_>,
+        value: v8::Local<'_, v8::Value>,
+    ) -> Result<Self, serde_v8::Error> {
+        let tc_scope = &mut v8::TryCatch::new(scope); // 捕获读取属性时抛出的异常
+        Self::from_v8_nested(tc_scope, value, &mut Vec::new())
+    }
+
+    /// 将 V8 值转换为 JsValue，记录正在转换的祖先对象以检测循环引用
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    /// - `value`: 要转换的 V8 值
+    /// - `ancestors`: 从根到当前值的所有对象（identity hash 和句柄），hash 只用于缩小比较范围
+    fn from_v8_nested(
+        scope: &mut v8::TryCatch<'_, v8::HandleScope<'_>>,
+        value: v8::Local<'_, v8::Value>,
+        ancestors: &mut Vec<(i32, v8::Global<v8::Object>)>,
+    ) -> Result<Self, serde_v8::Error> {
+        if value.is_undefined() || value.is_function() || value.is_symbol() {
+            return Ok(JsValue::Undefined);
+        }
+
+        if value.is_null() {
+            return Ok(JsValue::Null);
+        }
+
+        if value.is_boolean() {
+            return Ok(JsValue::Bool(value.is_true()));
+        }
+
+        if value.is_number() {
+            return Ok(JsValue::Number(
+                value.number_value(scope).unwrap_or(f64::NAN),
+            ));
+        }
+
+        if value.is_string() {
+            return Ok(JsValue::String(value.to_rust_string_lossy(scope)));
+        }
+
+        if let Ok(big_int) = value.try_cast::<v8::BigInt>() {
+            if let Some(value) = big_int_to_i128(big_int) {
+                return Ok(JsValue::BigInt(value));
+            }
+        }
+
+        // ArrayBuffer 的视图（Uint8Array、DataView 等）复制它引用的字节
+        if let Ok(view) = value.try_cast::<v8::ArrayBufferView>() {
+            let mut bytes = vec![0; view.byte_length()];
+            view.copy_contents(&mut bytes);
+            return Ok(JsValue::Bytes(bytes));
+        }
+
+        if let Ok(buffer) = value.try_cast::<v8::ArrayBuffer>() {
+            let backing_store = buffer.get_backing_store();
+            let bytes = backing_store[..buffer.byte_length()]
+                .iter()
+                .map(|byte| byte.get())
+                .collect();
+            return Ok(JsValue::Bytes(bytes));
+        }
+
+        let Ok(object) = value.try_cast::<v8::Object>() else {
+            // 其他原始值（例如超出范围的 BigInt）按字符串表示
+            return Ok(JsValue::String(value.to_rust_string_lossy(scope)));
+        };
+
+        // 嵌套过深或者引用了正在转换的祖先对象
+        let hash_id: i32 = object.get_identity_hash().into();
+        if ancestors.len() >= MAX_DEPTH
+            || ancestors
+                .iter()
+                .any(|(ancestor_hash, ancestor)| *ancestor_hash == hash_id && *ancestor == object)
+        {
+            return Ok(JsValue::Undefined);
+        }
+        ancestors.push((hash_id, v8::Global::new(scope, object)));
+
+        let result = if let Ok(array) = object.try_cast::<v8::Array>() {
+            // 数组按下标逐个转换
+            (0..array.length())
+                .map(|index| match array.get_index(scope, index) {
+                    Some(element) => JsValue::from_v8_nested(scope, element, ancestors),
+                    None => Err(caught_exception(scope)),
+                })
+                .collect::<Result<_, _>>()
+                .map(JsValue::Array)
+        } else {
+            JsValue::properties_from_v8(scope, object, ancestors).map(JsValue::Object)
+        };
+
+        ancestors.pop();
+        result
+    }
+
+    /// 转换对象自身可枚举的属性
+    ///
+    /// getter 和 Proxy 的 trap 可能抛出异常，V8 返回 None 时从 TryCatch 中取出异常作为错误
+    ///
+    /// # 参数
+    /// - `scope`: 捕获异常的 V8 作用域
+    /// - `object`: 要转换的对象
+    /// - `ancestors`: 从根到当前对象的所有对象
+    fn properties_from_v8(
+        scope: &mut v8::TryCatch<'_, v8::HandleScope<'_>>,
+        object: v8::Local<'_, v8::Object>,
+        ancestors: &mut Vec<(i32, v8::Global<v8::Object>)>,
+    ) -> Result<IndexMap<String, JsValue>, serde_v8::Error> {
+        let mut properties = IndexMap::new();
+        let Some(keys) = object.get_own_property_names(scope, Default::default()) else {
+            return Err(caught_exception(scope));
+        };
+        for index in 0..keys.length() {
+            let Some(key) = keys.get_index(scope, index) else {
+                return Err(caught_exception(scope));
+            };
+            let Some(property) = object.get(scope, key) else {
+                return Err(caught_exception(scope));
+            };
+            let key = key.to_rust_string_lossy(scope);
+            properties.insert(key, JsValue::from_v8_nested(scope, property, ancestors)?);
+        }
+        Ok(properties)
+    }
+
+    /// 将 JsValue 转换为 V8 值
+    ///
+    /// # 参数
+    /// - `scope`: V8 作用域
+    pub(crate) fn into_v8<'s>(self, scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Value> {
+        match self {
+            JsValue::Undefined => v8::undefined(scope).into(),
+            JsValue::Null => v8::null(scope).into(),
+            JsValue::Bool(value) => v8::Boolean::new(scope, value).into(),
+            JsValue::Number(value) => v8::Number::new(scope, value).into(),
+            JsValue::String(value) => v8::String::new(scope, &value).unwrap().into(),
+            JsValue::BigInt(value) => {
+                let magnitude = value.unsigned_abs();
+                let words = [magnitude as u64, (magnitude >> 64) as u64]; // 低位在前
+                v8::BigInt::new_from_words(scope, value < 0, &words)
+                    .unwrap()
+                    .into()
+            }
+            // 字节转换为 Uint8Array, 直接把 Vec 的内存交给 ArrayBuffer
+            JsValue::Bytes(bytes) => {
+                let length = bytes.len();
+                let backing_store =
+                    v8::ArrayBuffer::new_backing_store_from_vec(bytes).make_shared();
+                let buffer = v8::ArrayBuffer::with_backing_store(scope, &backing_store);
+                v8::Uint8Array::new(scope, buffer, 0, length)
+                    .unwrap()
+                    .into()
+            }
+            JsValue::Array(elements) => {
+                let elements = elements
+                    .into_iter()
+                    .map(|element| element.into_v8(scope))
+                    .collect::<Vec<_>>();
+                v8::Array::new_with_elements(scope, &elements).into()
+            }
+            JsValue::Object(properties) => {
+                let object = v8::Object::new(scope);
+                for (key, property) in properties {
+                    let key = v8::String::new(scope, &key).unwrap();
+                    let property = property.into_v8(scope);
+                    object.set(scope, key.into(), property);
+                }
+                object.into()
+            }
+        }
+    }
+
+    /// 将 JsValue 反序列化为任意实现了 Deserialize 的 Rust 类型
+    ///
+    /// # 示例
+    /// ```ignore
+    /// let total: u64 = runtime.execute("./main.js").await?.deserialize_into()?;
+    /// ```
+    pub fn deserialize_into<T: DeserializeOwned>(self) -> Result<T, serde_v8::Error> {
+        serde_v8::from_value(self)
+    }
+}
+
+/// 读取 BigInt 的值，超出 i128 范围时返回 None
+fn big_int_to_i128(big_int: v8::Local<'_, v8::BigInt>) -> Option<i128> {
+    if big_int.word_count() > 2 {
+        return None;
+    }
+
+    let mut words = [0u64; 2];
+    let (negative, words) = big_int.to_words_array(&mut words);
+    let magnitude = words
+        .iter()
+        .rev()
+        .fold(0u128, |magnitude, word| (magnitude << 64) | *word as u128);
+    let magnitude = i128::try_from(magnitude).ok()?;
+
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+/// 把读取属性时抛出的异常转换为转换错误
+fn caught_exception(scope: &mut v8::TryCatch<'_, v8::HandleScope<'_>>) -> serde_v8::Error {
+    let exception = JsException::from_try_catch(scope);
+    serde::de::Error::custom(format!("读取属性时抛出异常: {}", exception.message))
+}
@@ -0,0 +1,48 @@
+use zjs::{op, v8, BuiltinModule, ExecuteOptions, JsRuntime, JsValue, RuntimeOptions};
+
+op! {
+    fn double(value: f64) -> Result<f64, String> {
+        Ok(value * 2.0)
+    }
+}
+
+#[tokio::test]
+async fn builtins_support_named_and_default_imports() {
+    let math = BuiltinModule::new("app:math")
+        .function("double", double)
+        .value("pi", |scope| v8::Number::new(scope, 3.0).into());
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        extensions: vec![math],
+        ..Default::default()
+    });
+
+    let source = r#"
+        import math, { double, pi } from "app:math";
+        import fs, { openFile } from "fs";
+        export function main() {
+            return [double(pi), math.double === double, math.pi, fs.openFile === openFile];
+        }
+    "#;
+    let result = runtime
+        .eval_module_with_options("math.js", source, ExecuteOptions::new())
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        JsValue::Array(vec![
+            JsValue::Number(6.0),
+            JsValue::Bool(true),
+            JsValue::Number(3.0),
+            JsValue::Bool(true),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn importing_an_undeclared_name_fails() {
+    let mut runtime = JsRuntime::new();
+
+    let result = runtime
+        .eval_module("fs.js", "import { readFile } from 'fs';")
+        .await;
+    assert!(matches!(result, Err(zjs::JsError::Instantiate(e)) if e.message.contains("readFile")));
+}
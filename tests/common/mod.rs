@@ -0,0 +1,44 @@
+#![allow(dead_code)] // 每个测试文件只用到其中一部分
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// 测试用的临时目录，drop 时删除
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("zjs-test-{}-{}", std::process::id(), id));
+        fs::create_dir_all(&dir).unwrap();
+        Self(fs::canonicalize(dir).unwrap())
+    }
+
+    /// 临时目录的绝对路径
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// 创建文件（自动创建父目录），返回它的绝对路径
+    pub fn file(&self, relative: &str, content: &str) -> PathBuf {
+        let path = self.0.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// 创建文件，返回它的绝对路径字符串，用于 execute 和 eval_module
+    pub fn file_str(&self, relative: &str, content: &str) -> String {
+        self.file(relative, content).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::TempDir;
+use zjs::{JsError, JsRuntime, JsValue, UnhandledRejectionPolicy};
+
+#[tokio::test]
+async fn require_of_throwing_module_is_not_an_unhandled_rejection() {
+    let dir = TempDir::new();
+    dir.file("throws.mjs", "throw new Error('boom');");
+    let entry = dir.file_str(
+        "main.mjs",
+        r#"
+            import { createRequire } from "module";
+            const require = createRequire(import.meta.url);
+
+            export function main() {
+                try {
+                    require("./throws.mjs");
+                } catch (e) {
+                    return e.message;
+                }
+            }
+        "#,
+    );
+
+    let mut runtime = JsRuntime::new();
+    runtime.set_unhandled_rejection_policy(UnhandledRejectionPolicy::Error);
+    let result = runtime.execute(&entry).await;
+    assert!(matches!(result, Ok(JsValue::String(message)) if message == "boom"));
+}
+
+#[tokio::test]
+async fn require_rethrows_module_errors() {
+    let dir = TempDir::new();
+    dir.file("throws.cjs", "throw new Error('boom');");
+    let entry = dir.file_str(
+        "main.mjs",
+        r#"
+            import { createRequire } from "module";
+            const require = createRequire(import.meta.url);
+            export function main() {
+                require("./throws.cjs");
+            }
+        "#,
+    );
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&entry).await;
+    assert!(matches!(result, Err(JsError::Evaluate(e)) if e.message.contains("boom")));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::TempDir;
+use zjs::{JsRuntime, JsValue};
+
+#[tokio::test]
+async fn dynamic_import_loads_files_and_builtins() {
+    let dir = TempDir::new();
+    dir.file(
+        "plugins/hello.js",
+        "export const name = 'hello'; export default 42;",
+    );
+    let entry = dir.file_str(
+        "main.js",
+        r#"
+            export async function main() {
+                const plugin = await import("./plugins/hello.js");
+                const fs = await import("fs");
+                return [plugin.name, plugin.default, typeof fs.openFile];
+            }
+        "#,
+    );
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&entry).await.unwrap();
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::String("hello".into()),
+            JsValue::Number(42.0),
+            JsValue::String("function".into()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn dynamic_import_rejects_with_an_error() {
+    let dir = TempDir::new();
+    dir.file("broken.js", "throw new RangeError('broken plugin');");
+    let entry = dir.file_str(
+        "main.js",
+        r#"
+            async function failure(specifier) {
+                try {
+                    await import(specifier);
+                } catch (e) {
+                    return e instanceof Error ? `${e.name}: ${e.code ?? e.message}` : "not an error";
+                }
+            }
+
+            export async function main() {
+                return [await failure("./missing.js"), await failure("./broken.js")];
+            }
+        "#,
+    );
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&entry).await.unwrap();
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::String("Error: ERR_MODULE_NOT_FOUND".into()),
+            JsValue::String("RangeError: broken plugin".into()),
+        ])
+    );
+}
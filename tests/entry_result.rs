@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use zjs::{op, ExecuteOptions, JsError, JsRuntime, JsValue, OpFunction, RuntimeOptions};
+
+op! {
+    async fn sleep(ms: f64) -> Result<(), String> {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+        Ok(())
+    }
+}
+
+/// 执行源代码中导出的 main 函数
+async fn run_main(source: &str) -> Result<JsValue, JsError> {
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        ops: vec![OpFunction::new("sleep", sleep)],
+        ..Default::default()
+    });
+    runtime
+        .eval_module_with_options("main.js", source, ExecuteOptions::new())
+        .await
+}
+
+#[tokio::test]
+async fn returns_the_fulfilled_value() {
+    let source = r#"
+        export async function main() {
+            await sleep(10);
+            return { answer: 42, items: ["a", null] };
+        }
+    "#;
+
+    let result = run_main(source).await.unwrap();
+    let JsValue::Object(object) = result else {
+        panic!("应该返回对象");
+    };
+    assert_eq!(object["answer"], JsValue::Number(42.0));
+    assert_eq!(
+        object["items"],
+        JsValue::Array(vec![JsValue::String("a".into()), JsValue::Null])
+    );
+}
+
+#[tokio::test]
+async fn returns_rejections_as_errors() {
+    let source = r#"
+        export async function main() {
+            await sleep(10);
+            throw new TypeError("late failure");
+        }
+    "#;
+
+    let result = run_main(source).await;
+    assert!(matches!(result, Err(JsError::Evaluate(e)) if e.message == "TypeError: late failure"));
+}
+
+#[tokio::test]
+async fn reports_promises_that_never_settle() {
+    let source = "export function main() { return new Promise(() => {}); }";
+
+    let result = run_main(source).await;
+    assert!(matches!(result, Err(JsError::Evaluate(_))));
+}
@@ -0,0 +1,41 @@
+use zjs::{ExecuteOptions, JsRuntime, JsValue};
+
+const SOURCE: &str = r#"
+    globalThis.loaded = (globalThis.loaded ?? 0) + 1;
+    export function handler(name, count) { return `${name}:${count}`; }
+    export default function () { return "default"; }
+"#;
+
+#[tokio::test]
+async fn calls_the_named_export_with_arguments() {
+    let mut runtime = JsRuntime::new();
+    let options = ExecuteOptions::new().entrypoint("handler").arg("a").arg(2);
+
+    let result = runtime
+        .eval_module_with_options("entry.js", SOURCE, options)
+        .await;
+    assert_eq!(result.unwrap(), JsValue::String("a:2".into()));
+}
+
+#[tokio::test]
+async fn calls_the_default_export() {
+    let mut runtime = JsRuntime::new();
+    let options = ExecuteOptions::new().default_export();
+
+    let result = runtime
+        .eval_module_with_options("entry.js", SOURCE, options)
+        .await;
+    assert_eq!(result.unwrap(), JsValue::String("default".into()));
+}
+
+#[tokio::test]
+async fn runs_only_top_level_code_without_an_entrypoint() {
+    let mut runtime = JsRuntime::new();
+    let options = ExecuteOptions::new().no_entrypoint();
+
+    let result = runtime
+        .eval_module_with_options("entry.js", SOURCE, options)
+        .await;
+    assert_eq!(result.unwrap(), JsValue::Undefined);
+    assert!(runtime.get_export("handler").is_ok());
+}
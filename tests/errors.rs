@@ -0,0 +1,66 @@
+mod common;
+
+use common::TempDir;
+use zjs::{JsError, JsRuntime};
+
+#[tokio::test]
+async fn missing_files_are_load_errors() {
+    let dir = TempDir::new();
+    let missing = dir.path().join("missing.js");
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&missing.to_string_lossy()).await;
+    assert!(matches!(result, Err(JsError::Load(_))));
+}
+
+#[tokio::test]
+async fn syntax_errors_report_the_location() {
+    let dir = TempDir::new();
+    let entry = dir.file_str("bad.js", "const a = 1;\nexport const = ;\n");
+
+    let mut runtime = JsRuntime::new();
+    let Err(JsError::Compile(exception)) = runtime.execute(&entry).await else {
+        panic!("应该返回编译错误");
+    };
+    assert!(exception.message.starts_with("SyntaxError"));
+    assert!(exception.resource_name.unwrap().ends_with("bad.js"));
+    assert_eq!(exception.line_number, Some(2));
+}
+
+#[tokio::test]
+async fn missing_imports_are_instantiate_errors() {
+    let dir = TempDir::new();
+    dir.file("dep.js", "export const present = 1;");
+    let entry = dir.file_str("main.js", "import { absent } from './dep.js';");
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&entry).await;
+    assert!(matches!(result, Err(JsError::Instantiate(e)) if e.message.contains("absent")));
+}
+
+#[tokio::test]
+async fn thrown_exceptions_carry_the_stack() {
+    let dir = TempDir::new();
+    let entry = dir.file_str(
+        "main.js",
+        "function fail() { throw new TypeError('bad input'); }\nexport function main() { fail(); }\n",
+    );
+
+    let mut runtime = JsRuntime::new();
+    let Err(JsError::Evaluate(exception)) = runtime.execute(&entry).await else {
+        panic!("应该返回执行错误");
+    };
+    assert_eq!(exception.message, "TypeError: bad input");
+    assert!(exception.stack.as_deref().unwrap().contains("at fail"));
+    assert_eq!(exception.line_number, Some(1));
+}
+
+#[tokio::test]
+async fn modules_without_main_are_missing_entrypoint_errors() {
+    let mut runtime = JsRuntime::new();
+    let dir = TempDir::new();
+    let entry = dir.file_str("main.js", "export const value = 1;");
+
+    let result = runtime.execute(&entry).await;
+    assert!(matches!(result, Err(JsError::MissingEntrypoint(name)) if name == "main"));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::TempDir;
+use zjs::{ExecuteOptions, JsError, JsRuntime, JsValue};
+
+#[tokio::test]
+async fn inline_modules_resolve_imports_against_their_virtual_path() {
+    let dir = TempDir::new();
+    dir.file("lib/math.js", "export const double = (n) => n * 2;");
+    let name = dir.path().join("generated.js"); // 磁盘上不存在的虚拟路径
+
+    let source = r#"
+        import { double } from "./lib/math.js";
+        export function main() { return [double(21), import.meta.filename]; }
+    "#;
+    let mut runtime = JsRuntime::new();
+    let result = runtime
+        .eval_module_with_options(&name.to_string_lossy(), source, ExecuteOptions::new())
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        JsValue::Array(vec![
+            JsValue::Number(42.0),
+            JsValue::String(name.to_string_lossy().into_owned()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn scripts_return_their_completion_value() {
+    let mut runtime = JsRuntime::new();
+
+    let result = runtime.eval_script("const a = 20; a + 22").await;
+    assert_eq!(result.unwrap(), JsValue::Number(42.0));
+
+    // 值是 Promise 时返回它完成后的值
+    let result = runtime.eval_script("Promise.resolve('later')").await;
+    assert_eq!(result.unwrap(), JsValue::String("later".into()));
+}
+
+#[tokio::test]
+async fn scripts_cannot_use_static_imports() {
+    let mut runtime = JsRuntime::new();
+
+    let result = runtime.eval_script("import fs from 'fs';").await;
+    assert!(matches!(result, Err(JsError::Compile(_))));
+}
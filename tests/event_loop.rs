@@ -0,0 +1,67 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use zjs::{op, v8, ExecuteOptions, JsRuntime, JsValue, OpFunction, RuntimeOptions};
+
+op! {
+    async fn sleep(ms: f64) -> Result<(), String> {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+        Ok(())
+    }
+}
+
+static RELEASED: AtomicBool = AtomicBool::new(false); // hold() 的句柄是否已经释放
+
+/// 保持事件循环存活 50 毫秒的长生命周期句柄
+fn hold(scope: &mut v8::HandleScope, _args: v8::FunctionCallbackArguments, _rv: v8::ReturnValue) {
+    let handle = zjs::op::event_loop_handle(scope);
+    handle.ref_handle();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        RELEASED.store(true, Ordering::SeqCst);
+        handle.unref_handle();
+    });
+}
+
+fn runtime() -> JsRuntime {
+    JsRuntime::with_options(RuntimeOptions {
+        ops: vec![
+            OpFunction::new("sleep", sleep),
+            OpFunction::new("hold", hold),
+        ],
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn event_loop_exits_when_all_tasks_settle() {
+    let mut runtime = runtime();
+    let source = r#"
+        export async function main() {
+            await Promise.all([sleep(10), sleep(20)]);
+            sleep(30); // 没有 await 的任务也要等它完成
+            return "done";
+        }
+    "#;
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        runtime.eval_module_with_options("loop.js", source, ExecuteOptions::new()),
+    )
+    .await
+    .expect("事件循环没有退出");
+    assert_eq!(result.unwrap(), JsValue::String("done".into()));
+}
+
+#[tokio::test]
+async fn referenced_handles_keep_the_loop_alive_until_released() {
+    let mut runtime = runtime();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), runtime.eval_script("hold()"))
+        .await
+        .expect("句柄释放后事件循环没有退出");
+    assert!(result.is_ok());
+    assert!(RELEASED.load(Ordering::SeqCst));
+}
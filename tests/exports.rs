@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use zjs::{op, JsError, JsRuntime, JsValue, OpFunction, RuntimeOptions};
+
+op! {
+    async fn sleep(ms: f64) -> Result<(), String> {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+        Ok(())
+    }
+}
+
+const HANDLERS: &str = r#"
+    let count = 0;
+    export const name = "counter";
+    export function increment(by) { count += by; return count; }
+    export async function delayed(value) { await sleep(10); return value; }
+"#;
+
+#[tokio::test]
+async fn exported_functions_keep_module_state_between_calls() {
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        ops: vec![OpFunction::new("sleep", sleep)],
+        ..Default::default()
+    });
+    runtime.eval_module("handlers.js", HANDLERS).await.unwrap();
+
+    assert_eq!(
+        runtime.call_export("increment", [1]).await.unwrap(),
+        JsValue::Number(1.0)
+    );
+    assert_eq!(
+        runtime.call_export("increment", [2]).await.unwrap(),
+        JsValue::Number(3.0)
+    );
+    assert_eq!(
+        runtime.call_export("delayed", ["async"]).await.unwrap(),
+        JsValue::String("async".into())
+    );
+    assert_eq!(
+        runtime.get_export("name").unwrap(),
+        JsValue::String("counter".into())
+    );
+}
+
+#[tokio::test]
+async fn missing_exports_are_errors() {
+    let mut runtime = JsRuntime::new();
+    assert!(matches!(
+        runtime.get_export("name"),
+        Err(JsError::NotLoaded)
+    ));
+
+    runtime.eval_module("handlers.js", HANDLERS).await.unwrap();
+    assert!(matches!(
+        runtime.get_export("missing"),
+        Err(JsError::MissingExport(name)) if name == "missing"
+    ));
+    assert!(matches!(
+        runtime.call_export("name", Vec::<JsValue>::new()).await,
+        Err(JsError::MissingEntrypoint(name)) if name == "name"
+    ));
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::path::Path;
+
+use common::TempDir;
+use zjs::{BuiltinModule, ExecuteOptions, JsError, JsRuntime, JsValue, RuntimeOptions};
+
+/// 创建带有 JS 源代码实现的扩展模块的运行时
+fn runtime_with(extensions: Vec<BuiltinModule>) -> JsRuntime {
+    JsRuntime::with_options(RuntimeOptions {
+        extensions,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn builtins_can_import_other_builtins() {
+    let mut runtime = runtime_with(vec![
+        BuiltinModule::from_source("app:base", "export const base = 'base';"),
+        BuiltinModule::from_source(
+            "app:utils",
+            r#"
+                import { base } from "app:base";
+                import { readFileSync } from "node:fs";
+                export const value = `${base}:${typeof readFileSync}`;
+            "#,
+        ),
+    ]);
+
+    let source = r#"
+        import { value } from "app:utils";
+        export async function main() { return value; }
+    "#;
+    let result = runtime
+        .eval_module_with_options("entry.js", source, ExecuteOptions::new())
+        .await;
+    assert_eq!(result.unwrap(), JsValue::String("base:function".into()));
+
+    // 内置模块以虚拟的键注册, 不会被当作相对于当前工作目录的路径
+    let info = runtime.module_info("zjs:app:utils").unwrap();
+    assert_eq!(info.path, Path::new("zjs:app:utils"));
+    assert!(info
+        .dependencies
+        .contains(&Path::new("zjs:app:base").to_path_buf()));
+    assert!(runtime.module_info("node:fs").is_some());
+    assert!(runtime.module_info("app:utils").is_none());
+}
+
+#[tokio::test]
+async fn builtins_cannot_import_files() {
+    let dir = TempDir::new();
+    dir.file("helper.js", "export const value = 1;");
+    let entry = dir.file_str("entry.js", "import { value } from 'app:leaky';");
+
+    let mut runtime = runtime_with(vec![BuiltinModule::from_source(
+        "app:leaky",
+        "export { value } from './helper.js';",
+    )]);
+
+    // 即使入口模块所在的目录中存在 helper.js, 内置模块也不能 import 它
+    let result = runtime.execute(&entry).await;
+    assert!(
+        matches!(result, Err(JsError::Instantiate(e)) if e.message.contains("Cannot find module './helper.js'"))
+    );
+}
@@ -0,0 +1,44 @@
+use zjs::{JsError, JsRuntime, JsValue, RuntimeOptions};
+
+/// 不断分配内存直到堆耗尽的脚本
+const ALLOCATE_FOREVER: &str = r#"
+    const chunks = [];
+    while (true) {
+        chunks.push(new Array(100000).fill("x".repeat(16)));
+    }
+"#;
+
+fn runtime_with_small_heap() -> JsRuntime {
+    JsRuntime::with_options(RuntimeOptions {
+        max_heap_size: Some(32 * 1024 * 1024),
+        heap_limit_extension: Some(16 * 1024 * 1024),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn runaway_allocation_returns_out_of_memory() {
+    let mut runtime = runtime_with_small_heap();
+
+    let result = runtime.eval_module("oom.js", ALLOCATE_FOREVER).await;
+    assert!(matches!(result, Err(JsError::OutOfMemory)));
+
+    // 上下文已经被丢弃, 之前的导出无法再访问
+    assert!(matches!(
+        runtime.get_export("chunks"),
+        Err(JsError::NotLoaded)
+    ));
+}
+
+#[tokio::test]
+async fn runtime_recovers_after_each_out_of_memory() {
+    let mut runtime = runtime_with_small_heap();
+
+    for _ in 0..2 {
+        let result = runtime.eval_script(ALLOCATE_FOREVER).await;
+        assert!(matches!(result, Err(JsError::OutOfMemory)));
+
+        let result = runtime.eval_script("[1, 2, 3].length").await;
+        assert_eq!(result.unwrap(), JsValue::Number(3.0));
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::TempDir;
+use zjs::{JsRuntime, JsValue};
+
+#[tokio::test]
+async fn resolved_urls_can_be_imported() {
+    let dir = TempDir::new();
+    let dep = dir.file("dir with space/dep.js", "export const value = 'dep';");
+    let dep_url = format!("file://{}", dep.to_string_lossy().replace(' ', "%20"));
+    let entry = dir.file_str(
+        "main.js",
+        &format!(
+            r#"
+                import {{ value as staticValue }} from "{dep_url}";
+
+                export async function main() {{
+                    const url = import.meta.resolve("./dir with space/dep.js");
+                    const {{ value }} = await import(url);
+                    return [url, value, staticValue];
+                }}
+            "#
+        ),
+    );
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&entry).await.unwrap();
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::String(dep_url),
+            JsValue::String("dep".to_string()),
+            JsValue::String("dep".to_string()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn import_meta_describes_the_module() {
+    let dir = TempDir::new();
+    dir.file(
+        "dep.js",
+        "export const meta = [import.meta.filename, import.meta.main];",
+    );
+    let entry = dir.file_str(
+        "main.js",
+        r#"
+            import { meta } from "./dep.js";
+            export function main() {
+                return [import.meta.url, import.meta.filename, import.meta.dirname, import.meta.main, ...meta];
+            }
+        "#,
+    );
+    let dir_path = dir.path().to_string_lossy().into_owned();
+
+    let mut runtime = JsRuntime::new();
+    let result = runtime.execute(&entry).await.unwrap();
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::String(format!("file://{}", entry)),
+            JsValue::String(entry.clone()),
+            JsValue::String(dir_path.clone()),
+            JsValue::Bool(true),
+            JsValue::String(format!("{}/dep.js", dir_path)),
+            JsValue::Bool(false),
+        ])
+    );
+}
@@ -0,0 +1,53 @@
+use zjs::{ExecuteOptions, JsRuntime, JsValue};
+
+#[tokio::test]
+async fn node_prefixed_specifiers_share_the_bare_builtin() {
+    let mut runtime = JsRuntime::new();
+    let source = r#"
+        import fs from "fs";
+        import nodeFs from "node:fs";
+        export async function main() {
+            const dynamic = await import("node:fs");
+            return [fs === nodeFs, dynamic.default === fs];
+        }
+    "#;
+
+    let result = runtime
+        .eval_module_with_options("prefix.js", source, ExecuteOptions::new())
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        JsValue::Array(vec![JsValue::Bool(true), JsValue::Bool(true)])
+    );
+}
+
+#[tokio::test]
+async fn unsupported_node_builtins_have_a_clear_error() {
+    let mut runtime = JsRuntime::new();
+    let source = r#"
+        async function message(specifier) {
+            try {
+                await import(specifier);
+            } catch (e) {
+                return e.message;
+            }
+        }
+        export async function main() {
+            return [await message("node:events"), await message("events"), await message("node:nope")];
+        }
+    "#;
+
+    let result = runtime
+        .eval_module_with_options("unsupported.js", source, ExecuteOptions::new())
+        .await;
+    let JsValue::Array(messages) = result.unwrap() else {
+        panic!("应该返回数组");
+    };
+    let messages: Vec<String> = messages
+        .into_iter()
+        .map(|message| message.deserialize_into().unwrap())
+        .collect();
+    assert!(messages[0].contains("'node:events' is not supported"));
+    assert!(messages[1].contains("'events' is not supported"));
+    assert!(messages[2].contains("Cannot find module 'node:nope'"));
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use zjs::{op, ExecuteOptions, JsRuntime, JsValue, OpError, OpFunction, RuntimeOptions};
+
+op! {
+    /// 两数相加
+    fn add(a: f64, b: f64) -> Result<f64, String> {
+        Ok(a + b)
+    }
+}
+
+op! {
+    fn version() -> Result<&'static str, String> {
+        Ok("1.0")
+    }
+}
+
+op! {
+    fn lookup(key: String) -> Result<String, OpError> {
+        Err(OpError::new(format!("no entry for {}", key)).with_code("ENOKEY"))
+    }
+}
+
+op! {
+    async fn split(text: String, delay: u64) -> Result<Vec<String>, String> {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        Ok(text.split(',').map(str::to_string).collect())
+    }
+}
+
+op! {
+    async fn fail_later() -> Result<(), OpError> {
+        Err(OpError::type_error("rejected"))
+    }
+}
+
+async fn run(source: &str) -> JsValue {
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        ops: vec![
+            OpFunction::new("add", add),
+            OpFunction::new("version", version),
+            OpFunction::new("lookup", lookup),
+            OpFunction::new("split", split),
+            OpFunction::new("failLater", fail_later),
+        ],
+        ..Default::default()
+    });
+    runtime
+        .eval_module_with_options("ops.js", source, ExecuteOptions::new())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sync_ops_return_values_and_throw_errors() {
+    let source = r#"
+        export function main() {
+            let error;
+            try {
+                lookup("a");
+            } catch (e) {
+                error = [e.name, e.code, e.message];
+            }
+            return [add(1, 2), version(), error];
+        }
+    "#;
+
+    let result = run(source).await;
+    let strings = |values: &[&str]| {
+        JsValue::Array(
+            values
+                .iter()
+                .map(|v| JsValue::String(v.to_string()))
+                .collect(),
+        )
+    };
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::Number(3.0),
+            JsValue::String("1.0".into()),
+            strings(&["Error", "ENOKEY", "no entry for a"]),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn invalid_arguments_throw_type_errors() {
+    let source = r#"
+        export function main() {
+            try {
+                add("one", 2);
+            } catch (e) {
+                return [e instanceof TypeError, e.code];
+            }
+        }
+    "#;
+
+    let result = run(source).await;
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::Bool(true),
+            JsValue::String("ERR_INVALID_ARG_TYPE".into()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn async_ops_return_promises() {
+    let source = r#"
+        export async function main() {
+            const promise = split("a,b", 10);
+            const rejected = await failLater().catch((e) => e instanceof TypeError);
+            return [promise instanceof Promise, await promise, rejected];
+        }
+    "#;
+
+    let result = run(source).await;
+    assert_eq!(
+        result,
+        JsValue::Array(vec![
+            JsValue::Bool(true),
+            JsValue::Array(vec![
+                JsValue::String("a".into()),
+                JsValue::String("b".into())
+            ]),
+            JsValue::Bool(true),
+        ])
+    );
+}
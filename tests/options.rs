@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use zjs::{
+    op, Builtin, ExecuteOptions, JsError, JsRuntime, JsValue, OpFunction, RuntimeOptions,
+    TokioAsyncTaskManager,
+};
+
+op! {
+    async fn sleep(ms: f64) -> Result<f64, String> {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+        Ok(ms)
+    }
+}
+
+#[tokio::test]
+async fn defaults_enable_every_builtin_and_global() {
+    let mut runtime = JsRuntime::new();
+    let source = r#"
+        import { openFile } from "fs";
+        import { createRequire } from "node:module";
+        export const types = [typeof openFile, typeof createRequire, typeof print];
+    "#;
+    assert!(runtime.eval_module("defaults.js", source).await.is_ok());
+
+    let types = runtime.get_export("types").unwrap();
+    let types: Vec<String> = types.deserialize_into().unwrap();
+    assert_eq!(types, ["function", "function", "function"]);
+}
+
+#[tokio::test]
+async fn only_selected_builtins_and_globals_are_available() {
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        builtin_modules: vec![Builtin::Module],
+        globals: Vec::new(),
+        ..Default::default()
+    });
+
+    let result = runtime.eval_script("typeof print").await;
+    assert_eq!(result.unwrap(), JsValue::String("undefined".into()));
+
+    let result = runtime
+        .eval_module("module.js", "import { createRequire } from 'module';")
+        .await;
+    assert!(result.is_ok());
+
+    let result = runtime
+        .eval_module("fs.js", "import { openFile } from 'fs';")
+        .await;
+    assert!(matches!(result, Err(JsError::Instantiate(_))));
+}
+
+#[tokio::test]
+async fn custom_dispatcher_completes_more_tasks_than_its_capacity() {
+    let mut runtime = JsRuntime::with_options(RuntimeOptions {
+        task_dispatcher: TokioAsyncTaskManager::with_channel_capacity(1),
+        ops: vec![OpFunction::new("sleep", sleep)],
+        max_heap_size: Some(64 * 1024 * 1024),
+        ..Default::default()
+    });
+    let source = r#"
+        export async function main() {
+            const results = await Promise.all(Array.from({ length: 10 }, (_, i) => sleep(i)));
+            return results.reduce((a, b) => a + b, 0);
+        }
+    "#;
+
+    let result = runtime
+        .eval_module_with_options("tasks.js", source, ExecuteOptions::new())
+        .await;
+    assert_eq!(result.unwrap(), JsValue::Number(45.0));
+}
@@ -0,0 +1,55 @@
+use std::thread;
+
+use zjs::{JsRuntime, JsValue};
+
+#[tokio::test]
+async fn runtimes_can_be_created_one_after_another() {
+    for index in 0..3 {
+        let mut runtime = JsRuntime::new();
+        let result = runtime.eval_script(&format!("{} * 2", index)).await;
+        assert_eq!(result.unwrap(), JsValue::Number(index as f64 * 2.0));
+    }
+}
+
+#[tokio::test]
+async fn runtimes_on_the_same_thread_are_independent() {
+    let mut first = JsRuntime::new();
+    let mut second = JsRuntime::new();
+
+    first
+        .eval_script("globalThis.owner = 'first'")
+        .await
+        .unwrap();
+    let result = second.eval_script("typeof globalThis.owner").await;
+    assert_eq!(result.unwrap(), JsValue::String("undefined".into()));
+
+    // 同一线程中的实例按创建的相反顺序销毁
+    drop(second);
+    drop(first);
+}
+
+#[test]
+fn runtimes_run_concurrently_on_many_threads() {
+    let handles = (0..4)
+        .map(|index| {
+            thread::spawn(move || {
+                let tokio_runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+                tokio_runtime.block_on(async {
+                    let mut runtime = JsRuntime::new();
+                    runtime.eval_script(&format!("'thread ' + {}", index)).await
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (index, handle) in handles.into_iter().enumerate() {
+        let result = handle.join().unwrap();
+        assert_eq!(
+            result.unwrap(),
+            JsValue::String(format!("thread {}", index))
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use zjs::{serde_v8, ExecuteOptions, JsError, JsRuntime, JsValue};
+
+#[test]
+fn bytes_deserialize_as_sequences() {
+    let bytes: Vec<u8> = serde_v8::from_value(JsValue::Bytes(vec![1, 2, 3])).unwrap();
+    assert_eq!(bytes, vec![1, 2, 3]);
+
+    let array: [u8; 2] = serde_v8::from_value(JsValue::Bytes(vec![4, 5])).unwrap();
+    assert_eq!(array, [4, 5]);
+}
+
+#[test]
+fn large_integers_use_the_full_u64_range() {
+    let value: u64 = serde_v8::from_value(JsValue::Number(2f64.powi(63))).unwrap();
+    assert_eq!(value, 1 << 63);
+
+    let value: u64 = serde_v8::from_value(JsValue::Number(18446744073709549568.0)).unwrap();
+    assert_eq!(value, 18446744073709549568);
+
+    let value: i64 = serde_v8::from_value(JsValue::Number(-(2f64.powi(63)))).unwrap();
+    assert_eq!(value, i64::MIN);
+
+    assert!(serde_v8::from_value::<u64>(JsValue::Number(2f64.powi(64))).is_err());
+}
+
+/// 把 main 返回的 { tags, total } 转换为 Rust 值
+fn summary(value: JsValue) -> (String, u64) {
+    let object: BTreeMap<String, JsValue> = value.deserialize_into().unwrap();
+    (
+        object["tags"].clone().deserialize_into().unwrap(),
+        object["total"].clone().deserialize_into().unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn arguments_are_serialized() {
+    let mut runtime = JsRuntime::new();
+    let source = r#"
+        export function main(tags, limits, count) {
+            return { tags: tags.join(","), total: limits.low + limits.high + count };
+        }
+    "#;
+    let mut limits = BTreeMap::new();
+    limits.insert("low", 1u64);
+    limits.insert("high", 2u64);
+
+    let options = ExecuteOptions::new()
+        .arg(vec!["a", "b"])
+        .arg(&limits)
+        .arg(3);
+    let result = runtime
+        .eval_module_with_options("serde.js", source, options)
+        .await;
+    assert_eq!(summary(result.unwrap()), ("a,b".to_string(), 6));
+
+    // call_export 的参数同样通过 serde 转换, 不同类型的参数可以先转换为 JsValue
+    let args = [
+        serde_v8::to_value(&["c"]).unwrap(),
+        serde_v8::to_value(&limits).unwrap(),
+        JsValue::Number(4.0),
+    ];
+    let result = runtime.call_export("main", args).await;
+    assert_eq!(summary(result.unwrap()), ("c".to_string(), 7));
+}
+
+#[tokio::test]
+async fn unserializable_arguments_are_reported() {
+    let mut runtime = JsRuntime::new();
+    let mut map = BTreeMap::new();
+    map.insert(vec![1], 1); // 数组不能作为对象的属性名
+
+    let options = ExecuteOptions::new().arg(map);
+    let result = runtime
+        .eval_module_with_options("serde.js", "export function main() {}", options)
+        .await;
+    assert!(matches!(result, Err(JsError::Conversion(_))));
+}
+
+#[tokio::test]
+async fn throwing_getters_are_conversion_errors() {
+    let mut runtime = JsRuntime::new();
+    let source = r#"
+        export const proxy = new Proxy({}, { ownKeys() { throw new Error('keys'); } });
+        export function main() {
+            return { ok: 1, get broken() { throw new Error('getter'); } };
+        }
+    "#;
+
+    let result = runtime
+        .eval_module_with_options("getters.js", source, ExecuteOptions::new())
+        .await;
+    assert!(matches!(result, Err(JsError::Conversion(e)) if e.to_string().contains("getter")));
+
+    let result = runtime.get_export("proxy");
+    assert!(matches!(result, Err(JsError::Conversion(e)) if e.to_string().contains("keys")));
+
+    // 异常没有留在运行时中, 之后的调用不受影响
+    let result = runtime.eval_script("1 + 1").await;
+    assert_eq!(result.unwrap(), JsValue::Number(2.0));
+}
+
+#[tokio::test]
+async fn values_round_trip_through_v8() {
+    let mut runtime = JsRuntime::new();
+    runtime
+        .eval_module("identity.js", "export const identity = (value) => value;")
+        .await
+        .unwrap();
+
+    let mut object = BTreeMap::new();
+    object.insert("text".to_string(), JsValue::String("héllo".into()));
+    object.insert("flag".to_string(), JsValue::Bool(true));
+    object.insert("nothing".to_string(), JsValue::Null);
+    object.insert("big".to_string(), JsValue::BigInt(-(1 << 100)));
+    object.insert("bytes".to_string(), JsValue::Bytes(vec![0, 255]));
+    object.insert(
+        "list".to_string(),
+        JsValue::Array(vec![JsValue::Number(1.5), JsValue::Undefined]),
+    );
+    let value = serde_v8::to_value(&object).unwrap();
+
+    let result = runtime.call_export("identity", [value.clone()]).await;
+    assert_eq!(result.unwrap(), value);
+}
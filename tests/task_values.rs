@@ -0,0 +1,86 @@
+mod common;
+
+use common::TempDir;
+use zjs::{ExecuteOptions, JsRuntime, JsValue};
+
+#[tokio::test]
+async fn binary_files_are_read_as_bytes() {
+    let dir = TempDir::new();
+    let path = dir.path().join("data.bin");
+    std::fs::write(&path, [0xff, 0x00, 0xfe]).unwrap();
+
+    let source = r#"
+        import { openFile } from "fs";
+        export async function main(path) {
+            const file = await openFile(path);
+            const content = await file.content();
+            return [content instanceof Uint8Array, content];
+        }
+    "#;
+    let mut runtime = JsRuntime::new();
+    let options = ExecuteOptions::new().arg(path.to_string_lossy());
+    let result = runtime
+        .eval_module_with_options("binary.js", source, options)
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        JsValue::Array(vec![
+            JsValue::Bool(true),
+            JsValue::Bytes(vec![0xff, 0x00, 0xfe])
+        ])
+    );
+}
+
+#[tokio::test]
+async fn failures_reject_with_error_instances() {
+    let dir = TempDir::new();
+    let text = dir.file("text.txt", "");
+    let missing = dir.path().join("missing/dir/file.txt");
+
+    let source = r#"
+        import { openFile } from "fs";
+
+        const describe = (e) => [e instanceof Error, e.name, e.code];
+
+        export async function main(missing, text) {
+            const results = [];
+            try {
+                await openFile(missing);
+            } catch (e) {
+                results.push(describe(e));
+            }
+
+            const file = await openFile(text);
+            try {
+                file.write(42);
+            } catch (e) {
+                results.push(describe(e));
+            }
+            results.push(await file.write("hello"));
+            return results;
+        }
+    "#;
+    let mut runtime = JsRuntime::new();
+    let options = ExecuteOptions::new()
+        .arg(missing.to_string_lossy())
+        .arg(text.to_string_lossy());
+    let result = runtime
+        .eval_module_with_options("errors.js", source, options)
+        .await;
+
+    let error = |name: &str, code: &str| {
+        JsValue::Array(vec![
+            JsValue::Bool(true),
+            JsValue::String(name.into()),
+            JsValue::String(code.into()),
+        ])
+    };
+    assert_eq!(
+        result.unwrap(),
+        JsValue::Array(vec![
+            error("Error", "ENOENT"),
+            error("TypeError", "ERR_INVALID_ARG_TYPE"),
+            JsValue::Number(5.0),
+        ])
+    );
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use zjs::{op, JsError, JsRuntime, OpFunction, RuntimeOptions};
+
+op! {
+    async fn sleep(ms: f64) -> Result<(), String> {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+        Ok(())
+    }
+}
+
+fn runtime_with_timeout(timeout: Duration) -> JsRuntime {
+    JsRuntime::with_options(RuntimeOptions {
+        execution_timeout: Some(timeout),
+        ops: vec![OpFunction::new("sleep", sleep)],
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn timeout_terminates_infinite_loops() {
+    let mut runtime = runtime_with_timeout(Duration::from_millis(100));
+
+    let result = runtime.eval_module("loop.js", "while (true) {}").await;
+    assert!(matches!(result, Err(JsError::Terminated)));
+
+    // 被终止之后运行时可以继续使用
+    let result = runtime.eval_script("1 + 1").await;
+    assert!(matches!(result, Ok(zjs::JsValue::Number(n)) if n == 2.0));
+}
+
+#[tokio::test]
+async fn termination_handle_stops_execution_from_another_thread() {
+    let mut runtime = JsRuntime::new();
+    let handle = runtime.termination_handle();
+
+    let terminator = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        handle.terminate()
+    });
+
+    let result = runtime.eval_module("loop.js", "while (true) {}").await;
+    assert!(matches!(result, Err(JsError::Terminated)));
+    assert!(terminator.join().unwrap());
+}
+
+#[tokio::test]
+async fn terminated_tasks_do_not_block_the_next_execution() {
+    let mut runtime = runtime_with_timeout(Duration::from_millis(100));
+
+    let result = runtime.eval_module("slow.js", "await sleep(60000);").await;
+    assert!(matches!(result, Err(JsError::Terminated)));
+
+    // 之前的任务已经被取消, 新的执行不会等待它完成
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        runtime.eval_module("fast.js", "export const answer = 42;"),
+    )
+    .await
+    .expect("新的执行被之前的任务阻塞");
+    assert!(result.is_ok());
+    assert!(matches!(runtime.get_export("answer"), Ok(zjs::JsValue::Number(n)) if n == 42.0));
+}
+
+#[tokio::test]
+async fn successful_results_survive_a_late_timeout() {
+    let mut runtime = runtime_with_timeout(Duration::from_millis(50));
+
+    for _ in 0..20 {
+        let result = runtime.eval_script("40 + 2").await;
+        assert!(matches!(result, Ok(zjs::JsValue::Number(n)) if n == 42.0));
+    }
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use std::time::Duration;
+
+use common::TempDir;
+use zjs::{op, JsError, JsRuntime, JsValue, OpFunction, RuntimeOptions};
+
+op! {
+    async fn sleep(ms: f64) -> Result<(), String> {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+        Ok(())
+    }
+}
+
+fn runtime() -> JsRuntime {
+    JsRuntime::with_options(RuntimeOptions {
+        ops: vec![OpFunction::new("sleep", sleep)],
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn waits_for_top_level_await_in_dependencies() {
+    let dir = TempDir::new();
+    dir.file("config.js", "await sleep(20);\nexport const port = 8080;");
+    let entry = dir.file_str(
+        "main.js",
+        r#"
+            import { port } from "./config.js";
+            const host = await Promise.resolve("localhost");
+            export function main() { return `${host}:${port}`; }
+        "#,
+    );
+
+    let result = runtime().execute(&entry).await;
+    assert_eq!(result.unwrap(), JsValue::String("localhost:8080".into()));
+}
+
+#[tokio::test]
+async fn reports_rejected_module_evaluation() {
+    let dir = TempDir::new();
+    dir.file(
+        "config.js",
+        "await sleep(10);\nthrow new Error('config unavailable');",
+    );
+    let entry = dir.file_str(
+        "main.js",
+        "import './config.js';\nexport function main() {}",
+    );
+
+    let result = runtime().execute(&entry).await;
+    assert!(
+        matches!(result, Err(JsError::Evaluate(e)) if e.message.contains("config unavailable"))
+    );
+}
@@ -0,0 +1,106 @@
+use std::{cell::RefCell, rc::Rc};
+
+use zjs::{ExecuteOptions, JsError, JsRuntime, UnhandledRejectionPolicy};
+
+/// 顶层代码同步抛出异常的模块
+const THROWING_MODULE: &str = "throw new Error('boom');";
+
+/// 入口函数在第一个 await 之前抛出异常的模块
+const THROWING_ENTRY: &str = "export async function main() { throw new Error('boom'); }";
+
+/// 留下一个真正未处理的 rejection 的模块
+const UNHANDLED_MODULE: &str = "Promise.reject(new Error('lost'));";
+
+/// 创建使用 Hook 策略的运行时，返回收集到的 rejection 信息
+fn runtime_with_hook() -> (JsRuntime, Rc<RefCell<Vec<String>>>) {
+    let reported = Rc::new(RefCell::new(Vec::new()));
+    let mut runtime = JsRuntime::new();
+    runtime.set_unhandled_rejection_policy(UnhandledRejectionPolicy::Hook(Box::new({
+        let reported = reported.clone();
+        move |exception| reported.borrow_mut().push(exception.message.clone())
+    })));
+    (runtime, reported)
+}
+
+#[tokio::test]
+async fn warn_policy_reports_entry_errors_as_evaluate() {
+    let mut runtime = JsRuntime::new();
+
+    let result = runtime.eval_module("throwing.js", THROWING_MODULE).await;
+    assert!(matches!(result, Err(JsError::Evaluate(e)) if e.message.contains("boom")));
+
+    let result = runtime
+        .eval_module_with_options("entry.js", THROWING_ENTRY, ExecuteOptions::new())
+        .await;
+    assert!(matches!(result, Err(JsError::Evaluate(e)) if e.message.contains("boom")));
+
+    // 只打印警告, 执行本身成功
+    let result = runtime.eval_module("unhandled.js", UNHANDLED_MODULE).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn error_policy_only_fails_on_real_unhandled_rejections() {
+    let mut runtime = JsRuntime::new();
+    runtime.set_unhandled_rejection_policy(UnhandledRejectionPolicy::Error);
+
+    let result = runtime.eval_module("throwing.js", THROWING_MODULE).await;
+    assert!(matches!(result, Err(JsError::Evaluate(e)) if e.message.contains("boom")));
+
+    let result = runtime
+        .eval_module_with_options("entry.js", THROWING_ENTRY, ExecuteOptions::new())
+        .await;
+    assert!(matches!(result, Err(JsError::Evaluate(e)) if e.message.contains("boom")));
+
+    let result = runtime.eval_module("unhandled.js", UNHANDLED_MODULE).await;
+    assert!(matches!(result, Err(JsError::UnhandledRejection(e)) if e.message.contains("lost")));
+}
+
+#[tokio::test]
+async fn hook_policy_receives_only_real_unhandled_rejections() {
+    let (mut runtime, reported) = runtime_with_hook();
+
+    assert!(runtime
+        .eval_module("throwing.js", THROWING_MODULE)
+        .await
+        .is_err());
+    assert!(runtime
+        .eval_module_with_options("entry.js", THROWING_ENTRY, ExecuteOptions::new())
+        .await
+        .is_err());
+    assert!(reported.borrow().is_empty());
+
+    assert!(runtime
+        .eval_module("unhandled.js", UNHANDLED_MODULE)
+        .await
+        .is_ok());
+    assert_eq!(reported.borrow().len(), 1);
+    assert!(reported.borrow()[0].contains("lost"));
+}
+
+#[tokio::test]
+async fn rejections_do_not_carry_over_between_executions() {
+    let (mut runtime, reported) = runtime_with_hook();
+
+    // 脚本同步抛出异常, 之前被 reject 的 Promise 还没有经过微任务检查点
+    let result = runtime
+        .eval_script("Promise.reject(new Error('stale')); throw new Error('boom');")
+        .await;
+    assert!(matches!(result, Err(JsError::Evaluate(_))));
+
+    assert!(runtime.eval_module("empty.js", "").await.is_ok());
+    assert!(reported.borrow().is_empty());
+}
+
+#[tokio::test]
+async fn handled_rejections_are_not_reported() {
+    let (mut runtime, reported) = runtime_with_hook();
+
+    let source = r#"
+        const promise = Promise.reject(new Error('later'));
+        await null;
+        promise.catch(() => {});
+    "#;
+    assert!(runtime.eval_module("handled.js", source).await.is_ok());
+    assert!(reported.borrow().is_empty());
+}